@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::config_file::read_key_value_file;
+
+pub const DEFAULT_USERS_FILE: &'static str = "users.conf";
+
+// Ordered by privilege, so that a role can run any command requiring the same or a lower role
+#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Copy)]
+pub enum Role {
+    Viewer,
+    Editor,
+    Admin,
+}
+
+impl Role {
+    pub fn from_name(name: &str) -> Option<Role> {
+        match name {
+            "viewer" => Some(Role::Viewer),
+            "editor" => Some(Role::Editor),
+            "admin" => Some(Role::Admin),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Editor => "editor",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug)]
+struct Session {
+    user_name: String,
+    role: Role,
+}
+
+// Users are identified by name only (there are no passwords) - this is to stop accidents, not to provide security.
+//  Access control is only disabled (allowing every command) when the settings ask for it, as for the default here.
+#[derive(Eq, PartialEq, Debug, Default)]
+pub struct AccessControl {
+    enabled: bool,
+    users: BTreeMap<String, Role>,
+    session: Option<Session>,
+}
+
+impl AccessControl {
+    pub fn new(users: BTreeMap<String, Role>) -> AccessControl {
+        AccessControl { enabled: true, users, session: None }
+    }
+
+    // Unlike the other config files, the users file has to exist - a mistyped path mustn't let everyone do anything
+    pub fn load(file_path: &Path) -> Result<AccessControl, String> {
+        if !file_path.exists() {
+            return Err(format!(
+                "Users file \"{}\" does not exist - create it, or use --disable-access-control to allow every command",
+                file_path.display()
+            ));
+        }
+        let mut users = BTreeMap::new();
+        for (user_name, role_name) in read_key_value_file(file_path)? {
+            match Role::from_name(&role_name) {
+                Some(role) => {
+                    users.insert(user_name, role);
+                }
+                None => {
+                    return Err(format!(
+                        "Unknown role \"{}\" for user \"{}\" in \"{}\" - expected viewer, editor or admin",
+                        role_name, user_name, file_path.display()
+                    ));
+                }
+            }
+        }
+        if users.is_empty() {
+            return Err(format!(
+                "Users file \"{}\" has no users - add some, or use --disable-access-control to allow every command",
+                file_path.display()
+            ));
+        }
+        Ok(AccessControl::new(users))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn log_in(&mut self, user_name: &str) -> Result<String, String> {
+        match self.users.get(user_name) {
+            Some(role) => {
+                self.session = Some(Session { user_name: user_name.to_string(), role: *role });
+                Ok(format!("Logged in as \"{}\" with role {}", user_name, role.name()))
+            }
+            None => Err(format!("Unknown user \"{}\"", user_name))
+        }
+    }
+
+    pub fn check_permission(&self, required_role: Role, command_text: &str) -> Result<(), String> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        match &self.session {
+            None => Err(format!("Must be logged in to run \"{}\"", command_text)),
+            Some(session) if session.role < required_role => Err(format!(
+                "User \"{}\" (role {}) is not permitted to run \"{}\" - requires role {}",
+                session.user_name, session.role.name(), command_text, required_role.name()
+            )),
+            Some(_) => Ok(())
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{AccessControl, Role};
+    use std::collections::BTreeMap;
+    use std::env;
+    use std::fs;
+
+    fn access_control_with_users() -> AccessControl {
+        let mut users = BTreeMap::new();
+        users.insert("vera".to_string(), Role::Viewer);
+        users.insert("ed".to_string(), Role::Editor);
+        users.insert("ada".to_string(), Role::Admin);
+        AccessControl::new(users)
+    }
+
+    #[test]
+    fn test_disabled_access_control_permits_everything() {
+        assert_eq!(AccessControl::default().check_permission(Role::Admin, "Delete department Pies"), Ok(()));
+    }
+
+    #[test]
+    fn test_must_log_in_when_users_configured() {
+        assert_eq!(
+            access_control_with_users().check_permission(Role::Viewer, "List departments"),
+            Err("Must be logged in to run \"List departments\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unknown_user_cannot_log_in() {
+        assert_eq!(access_control_with_users().log_in("mallory"), Err("Unknown user \"mallory\"".to_string()));
+    }
+
+    #[test]
+    fn test_permission_depends_on_role() {
+        let mut access = access_control_with_users();
+        assert_eq!(access.log_in("ed"), Ok("Logged in as \"ed\" with role editor".to_string()));
+        assert_eq!(access.check_permission(Role::Viewer, "List departments"), Ok(()));
+        assert_eq!(access.check_permission(Role::Editor, "Add Bob to Pies"), Ok(()));
+        assert_eq!(
+            access.check_permission(Role::Admin, "Delete department Pies"),
+            Err("User \"ed\" (role editor) is not permitted to run \"Delete department Pies\" - requires role admin"
+                .to_string())
+        );
+    }
+
+    #[test]
+    fn test_load_users_from_file() {
+        let path = env::temp_dir().join(format!("employee-management-users-{}.conf", std::process::id()));
+        fs::write(&path, "# Users\nada = admin\nvera = viewer\n").unwrap();
+        let loaded = AccessControl::load(&path);
+        fs::write(&path, "ada = superuser\n").unwrap();
+        let invalid = AccessControl::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        let mut expected_users = BTreeMap::new();
+        expected_users.insert("ada".to_string(), Role::Admin);
+        expected_users.insert("vera".to_string(), Role::Viewer);
+        assert_eq!(loaded, Ok(AccessControl::new(expected_users)));
+        assert!(invalid.unwrap_err().starts_with("Unknown role \"superuser\" for user \"ada\""));
+    }
+
+    #[test]
+    fn test_load_rejects_missing_or_empty_users_file() {
+        let path = env::temp_dir().join(format!("employee-management-no-users-{}.conf", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let missing = AccessControl::load(&path);
+        fs::write(&path, "# No users yet\n").unwrap();
+        let empty = AccessControl::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(missing, Err(format!(
+            "Users file \"{}\" does not exist - create it, or use --disable-access-control to allow every command",
+            path.display()
+        )));
+        assert_eq!(empty, Err(format!(
+            "Users file \"{}\" has no users - add some, or use --disable-access-control to allow every command",
+            path.display()
+        )));
+        assert!(!AccessControl::default().is_enabled());
+    }
+}
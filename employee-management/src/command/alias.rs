@@ -0,0 +1,369 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use regex::Regex;
+
+use crate::config_file::{read_key_value_file, write_key_value_file, KeyValuePairs};
+
+pub const DEFAULT_ALIAS_FILE: &'static str = "aliases.conf";
+const MAX_EXPANSION_DEPTH: usize = 10;
+const MACRO_SEPARATOR: char = ';';
+
+// Names are restricted to single words so that the first word of any command text can be looked up as an alias
+const ALIAS_NAME_PATTERN: &'static str = r"^[A-Za-z0-9_-]+$";
+const PLACEHOLDER_PATTERN: &'static str = r"\{(?P<index>[0-9]+)\}";
+
+#[derive(Debug, PartialEq)]
+pub struct AliasRegistry {
+    aliases: BTreeMap<String, String>,
+    file_path: Option<PathBuf>,
+}
+
+fn first_word(text: &str) -> &str {
+    text.split_whitespace().next().unwrap_or("")
+}
+
+fn split_macro(template: &str) -> Vec<&str> {
+    template
+        .split(MACRO_SEPARATOR)
+        .map(|command| command.trim())
+        .filter(|command| !command.is_empty())
+        .collect()
+}
+
+// Placeholders count from {1}, so {0} (or an index too big to be an argument number) is an error
+fn placeholder_index(name: &str, captures: &regex::Captures) -> Result<usize, String> {
+    match captures["index"].parse::<usize>() {
+        Ok(index) if index >= 1 => Ok(index),
+        _ => Err(format!(
+            "Alias \"{}\" has invalid placeholder \"{}\" - placeholders count from {{1}}", name, &captures[0]
+        )),
+    }
+}
+
+// The number of arguments the template takes, which is its highest placeholder index
+fn count_placeholders(name: &str, template: &str) -> Result<usize, String> {
+    let mut count = 0;
+    for captures in Regex::new(PLACEHOLDER_PATTERN).unwrap().captures_iter(template) {
+        count = std::cmp::max(count, placeholder_index(name, &captures)?);
+    }
+    Ok(count)
+}
+
+// Splits invocation args on whitespace, except within double quotes - so "hire Bob "Pie QC"" gives ["Bob", "Pie QC"]
+fn split_args(text: &str) -> Result<Vec<String>, String> {
+    let mut args = vec![];
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_current = false;
+    for c in text.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_current = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_current {
+                    args.push(current.clone());
+                    current.clear();
+                    has_current = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_current = true;
+            }
+        }
+    }
+    if in_quotes {
+        return Err(format!("Unterminated quote in alias arguments \"{}\"", text));
+    }
+    if has_current {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+fn instantiate(name: &str, template: &str, args: &[String]) -> Result<Vec<String>, String> {
+    let expected_arg_count = count_placeholders(name, template)?;
+    if args.len() != expected_arg_count {
+        return Err(format!(
+            "Alias \"{}\" expects {} argument(s) but got {}", name, expected_arg_count, args.len()
+        ));
+    }
+    let placeholder_regex = Regex::new(PLACEHOLDER_PATTERN).unwrap();
+    let mut commands = vec![];
+    for command in split_macro(template) {
+        let mut instantiated = String::new();
+        let mut copied_up_to = 0;
+        for captures in placeholder_regex.captures_iter(command) {
+            let placeholder = captures.get(0).unwrap();
+            // Every index is between 1 and the number of args, as checked by counting the placeholders above
+            let arg = &args[placeholder_index(name, &captures)? - 1];
+            instantiated.push_str(&command[copied_up_to..placeholder.start()]);
+            instantiated.push_str(arg);
+            copied_up_to = placeholder.end();
+        }
+        instantiated.push_str(&command[copied_up_to..]);
+        commands.push(instantiated);
+    }
+    Ok(commands)
+}
+
+impl AliasRegistry {
+    pub fn new() -> AliasRegistry {
+        AliasRegistry { aliases: BTreeMap::new(), file_path: None }
+    }
+
+    // Aliases in the file are checked just as if they were being defined, so a hand-edited file can't hide a command
+    pub fn load(file_path: PathBuf, reserved_words: &[String]) -> Result<AliasRegistry, String> {
+        let mut registry = AliasRegistry::new();
+        for (name, template) in read_key_value_file(&file_path)? {
+            registry.define(&name, &template, reserved_words)
+                .map_err(|e| format!("Could not load aliases from \"{}\" - {}", file_path.display(), e))?;
+        }
+        registry.file_path = Some(file_path);
+        Ok(registry)
+    }
+
+    fn save(&self) -> Result<(), String> {
+        match &self.file_path {
+            Some(path) => {
+                let pairs: KeyValuePairs = self.aliases
+                    .iter()
+                    .map(|(name, template)| (name.clone(), template.clone()))
+                    .collect();
+                write_key_value_file(path, &pairs)
+            }
+            None => Ok(())
+        }
+    }
+
+    // Follows the first word of each command in the alias's template through other aliases,
+    //  to check whether adding the alias would let it (indirectly) expand to itself.
+    fn refers_back_to(&self, name: &str, template: &str, visited: &mut Vec<String>) -> bool {
+        for command in split_macro(template) {
+            let referenced = first_word(command);
+            if referenced == name {
+                return true;
+            }
+            if visited.iter().any(|v| v == referenced) {
+                continue;
+            }
+            visited.push(referenced.to_string());
+            if let Some(referenced_template) = self.aliases.get(referenced) {
+                if self.refers_back_to(name, referenced_template, visited) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    pub fn define(&mut self, name: &str, template: &str, reserved_words: &[String]) -> Result<String, String> {
+        if !Regex::new(ALIAS_NAME_PATTERN).unwrap().is_match(name) {
+            return Err(format!("Alias name \"{}\" must be a single word of letters, digits, '-' or '_'", name));
+        }
+        if reserved_words.iter().any(|word| word == name) {
+            return Err(format!("Alias name \"{}\" would hide an existing command", name));
+        }
+        if split_macro(template).is_empty() {
+            return Err(format!("Alias \"{}\" must expand to at least one command", name));
+        }
+        count_placeholders(name, template)?;
+        if self.refers_back_to(name, template, &mut vec![]) {
+            return Err(format!("Alias \"{}\" would expand to itself", name));
+        }
+        self.aliases.insert(name.to_string(), template.to_string());
+        self.save()?;
+        Ok(format!("Successfully defined alias \"{}\" as \"{}\"", name, template))
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<String, String> {
+        match self.aliases.remove(name) {
+            Some(_) => {
+                self.save()?;
+                Ok(format!("Successfully removed alias \"{}\"", name))
+            }
+            None => Err(format!("Alias \"{}\" does not exist", name))
+        }
+    }
+
+    pub fn describe_all(&self) -> Vec<String> {
+        self.aliases
+            .iter()
+            .map(|(name, template)| format!("{} = {}", name, template))
+            .collect()
+    }
+
+    // Returns the sequence of (non-alias) commands that the given command text stands for -
+    //  just the command text itself if it does not start with an alias name.
+    pub fn expand(&self, command_text: &str) -> Result<Vec<String>, String> {
+        self.expand_with_stack(command_text, &mut vec![])
+    }
+
+    fn expand_with_stack(&self, command_text: &str, stack: &mut Vec<String>) -> Result<Vec<String>, String> {
+        let name = first_word(command_text);
+        let template = match self.aliases.get(name) {
+            Some(template) => template,
+            None => return Ok(vec![command_text.to_string()]),
+        };
+        if stack.iter().any(|n| n == name) || stack.len() >= MAX_EXPANSION_DEPTH {
+            stack.push(name.to_string());
+            return Err(format!("Alias expansion is recursive ({})", stack.join(" -> ")));
+        }
+        let args = split_args(&command_text.trim_start()[name.len()..])?;
+        debug!("Expanding alias \"{}\" with args {:?}", name, args);
+        stack.push(name.to_string());
+        let mut expanded = vec![];
+        for command in instantiate(name, template, &args)? {
+            expanded.extend(self.expand_with_stack(&command, stack)?);
+        }
+        stack.pop();
+        Ok(expanded)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{split_args, AliasRegistry};
+    use std::env;
+    use std::fs;
+
+    fn registry_with(aliases: Vec<(&str, &str)>) -> AliasRegistry {
+        let mut registry = AliasRegistry::new();
+        for (name, template) in aliases {
+            registry.define(name, template, &[]).unwrap();
+        }
+        registry
+    }
+
+    fn strings(values: Vec<&str>) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_split_args_handles_quotes() {
+        assert_eq!(split_args(" Bob \"Pie QC\" \"\""), Ok(strings(vec!["Bob", "Pie QC", ""])));
+    }
+
+    #[test]
+    fn test_split_args_errors_on_unterminated_quote() {
+        assert_eq!(
+            split_args(" Bob \"Pie QC"),
+            Err("Unterminated quote in alias arguments \" Bob \"Pie QC\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_non_alias_command_expands_to_itself() {
+        let registry = registry_with(vec![("hire", "Add {1} to {2}")]);
+        assert_eq!(registry.expand("List departments"), Ok(strings(vec!["List departments"])));
+    }
+
+    #[test]
+    fn test_expands_alias_with_args() {
+        let registry = registry_with(vec![("hire", "Add {1} to {2}")]);
+        assert_eq!(registry.expand("hire Bob \"Pie QC\""), Ok(strings(vec!["Add Bob to Pie QC"])));
+    }
+
+    #[test]
+    fn test_expands_macro_through_nested_alias() {
+        let registry = registry_with(vec![
+            ("hire", "Add {1} to {2}"),
+            ("onboard", "hire {1} {2}; hire {1} Everyone"),
+        ]);
+        assert_eq!(
+            registry.expand("onboard Bob Pies"),
+            Ok(strings(vec!["Add Bob to Pies", "Add Bob to Everyone"]))
+        );
+    }
+
+    #[test]
+    fn test_errors_on_wrong_number_of_args() {
+        let registry = registry_with(vec![("hire", "Add {1} to {2}")]);
+        assert_eq!(
+            registry.expand("hire Bob"),
+            Err("Alias \"hire\" expects 2 argument(s) but got 1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_expansion_stops_at_maximum_depth() {
+        let mut registry = AliasRegistry::new();
+        for i in 0..11 {
+            registry.define(&format!("a{}", i), &format!("a{}", i + 1), &[]).unwrap();
+        }
+        assert_eq!(
+            registry.expand("a0"),
+            Err("Alias expansion is recursive (a0 -> a1 -> a2 -> a3 -> a4 -> a5 -> a6 -> a7 -> a8 -> a9 -> a10)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_define_rejects_self_reference_via_other_alias() {
+        let mut registry = registry_with(vec![("a", "b"), ("b", "List departments")]);
+        assert_eq!(registry.define("b", "a", &[]), Err("Alias \"b\" would expand to itself".to_string()));
+    }
+
+    #[test]
+    fn test_define_rejects_reserved_and_invalid_names() {
+        let mut registry = AliasRegistry::new();
+        assert_eq!(
+            registry.define("Add", "List departments", &strings(vec!["Add"])),
+            Err("Alias name \"Add\" would hide an existing command".to_string())
+        );
+        assert_eq!(
+            registry.define("two words", "List departments", &[]),
+            Err("Alias name \"two words\" must be a single word of letters, digits, '-' or '_'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_remove_alias() {
+        let mut registry = registry_with(vec![("hire", "Add {1} to {2}")]);
+        assert_eq!(registry.remove("hire"), Ok("Successfully removed alias \"hire\"".to_string()));
+        assert_eq!(registry.remove("hire"), Err("Alias \"hire\" does not exist".to_string()));
+    }
+
+    #[test]
+    fn test_aliases_persist_to_file() {
+        let path = env::temp_dir().join(format!("employee-management-aliases-{}.conf", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut registry = AliasRegistry::load(path.clone(), &[]).unwrap();
+        registry.define("hire", "Add {1} to {2}", &[]).unwrap();
+        let reloaded = AliasRegistry::load(path.clone(), &[]).unwrap();
+
+        assert_eq!(reloaded.describe_all(), strings(vec!["hire = Add {1} to {2}"]));
+
+        // A hand-edited alias is still not allowed to hide a command
+        let reloaded_with_reserved_hire = AliasRegistry::load(path.clone(), &strings(vec!["hire"]));
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            reloaded_with_reserved_hire,
+            Err(format!(
+                "Could not load aliases from \"{}\" - Alias name \"hire\" would hide an existing command",
+                path.display()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_define_rejects_placeholders_that_are_not_argument_numbers() {
+        let mut registry = AliasRegistry::new();
+        assert_eq!(
+            registry.define("z", "Add {0} to Pies", &[]),
+            Err("Alias \"z\" has invalid placeholder \"{0}\" - placeholders count from {1}".to_string())
+        );
+        assert_eq!(
+            registry.define("z", "Add {1} to {99999999999999999999999}", &[]),
+            Err(
+                "Alias \"z\" has invalid placeholder \"{99999999999999999999999}\" - placeholders count from {1}"
+                    .to_string()
+            )
+        );
+        assert_eq!(registry.expand("z"), Ok(strings(vec!["z"])));
+    }
+}
@@ -0,0 +1,69 @@
+use std::io::{self, IsTerminal};
+
+use mockall_derive::automock;
+
+use crate::locale::text;
+
+#[automock]
+pub trait Confirm {
+    fn confirm(&self, prompt: &str) -> bool;
+}
+
+pub struct StdinConfirmation;
+
+fn is_yes_answer(answer: &str) -> bool {
+    let answer = answer.trim().to_lowercase();
+    text("confirmation.yes_answers").split(',').any(|yes_answer| yes_answer.trim() == answer)
+}
+
+impl Confirm for StdinConfirmation {
+    // Anything other than a "yes" answer in the current language (including failing to read stdin) counts as not
+    //  confirmed
+    fn confirm(&self, prompt: &str) -> bool {
+        // When commands are piped in, reading an answer would swallow the next command instead
+        if !io::stdin().is_terminal() {
+            warn!("Cannot ask for confirmation as stdin is not a terminal - treating the command as not confirmed");
+            return false;
+        }
+        println!("{} {}", prompt, text("confirmation.suffix"));
+        let mut buffer = String::new();
+        match io::stdin().read_line(&mut buffer) {
+            Ok(_) => {
+                is_yes_answer(&buffer)
+            }
+            Err(e) => {
+                error!("Could not read confirmation from stdin: {:?}", e);
+                false
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{is_yes_answer, Confirm, StdinConfirmation};
+    use crate::locale::set_language;
+    use std::io::{self, IsTerminal};
+
+    #[test]
+    fn test_yes_answers_depend_on_language() {
+        assert!(is_yes_answer("y\n"));
+        assert!(is_yes_answer(" YES "));
+        assert!(!is_yes_answer("oui"));
+        assert!(!is_yes_answer(""));
+        set_language("fr").unwrap();
+        assert!(is_yes_answer("Oui\n"));
+        assert!(is_yes_answer("o"));
+        assert!(!is_yes_answer("y"));
+        set_language("en").unwrap();
+    }
+
+    #[test]
+    fn test_refuses_without_asking_when_stdin_is_not_a_terminal() {
+        // Only checkable when the tests themselves are run without a terminal (as under CI)
+        if !io::stdin().is_terminal() {
+            assert!(!StdinConfirmation {}.confirm("Are you sure?"));
+        }
+    }
+}
@@ -1,38 +1,232 @@
+use regex::Regex;
+
+use crate::access::{AccessControl, Role};
 use crate::employee_store::EmployeeStore;
+use crate::locale::{message, text};
+use super::alias::AliasRegistry;
+use super::confirmation::{Confirm, StdinConfirmation};
 use super::HandleCommand;
 
+const ALIAS_COMMANDS: [&'static str; 3] = ["define_alias", "remove_alias", "list_aliases"];
+const PLACEHOLDER_PATTERN: &'static str = r"\([^)]*\)";
+const AMBIGUITY_CHECK_FILLER: &'static str = "X";
+
+fn dry_run_message(preview: String) -> String {
+    message("dispatcher.dry_run", &[("message", &preview)])
+}
+
+// The first word of every command - aliases named after one of these would stop that command being usable
+pub fn reserved_words<E: 'static + EmployeeStore, H: HandleCommand<E>>(command_handlers: &[H]) -> Vec<String> {
+    command_handlers
+        .iter()
+        .filter_map(|handler| handler.describe().split_whitespace().next().map(|w| w.to_string()))
+        .chain(ALIAS_COMMANDS.iter().filter_map(|command| {
+            text(&format!("{}.description", command)).split_whitespace().next().map(|w| w.to_string())
+        }))
+        .collect()
+}
+
 pub struct CommandDispatcher<E: 'static + EmployeeStore, H: HandleCommand<E>> {
     command_handlers: Vec<H>,
     employee_store: E,
+    aliases: AliasRegistry,
+    access: AccessControl,
+    confirmation: Box<dyn Confirm>,
+    // In a dry run, commands that would change anything report what they would do instead
+    dry_run: bool,
 }
 
-pub fn create_dispatcher<E: 'static + EmployeeStore, H: HandleCommand<E>>(command_handlers: Vec<H>, employee_store: E)
-    -> CommandDispatcher<E, H>
+pub fn create_dispatcher<E: 'static + EmployeeStore, H: HandleCommand<E>>(
+    command_handlers: Vec<H>, employee_store: E, aliases: AliasRegistry, access: AccessControl
+) -> CommandDispatcher<E, H>
 {
-    CommandDispatcher { command_handlers, employee_store }
+    CommandDispatcher {
+        command_handlers, employee_store, aliases, access, confirmation: Box::new(StdinConfirmation {}), dry_run: false,
+    }
 }
 
 impl<E: 'static + EmployeeStore, H: HandleCommand<E>> CommandDispatcher<E, H> {
 
+    pub fn access_control_enabled(&self) -> bool {
+        self.access.is_enabled()
+    }
+
+    pub fn log_in(&mut self, user_name: &str) -> Result<String, String> {
+        self.access.log_in(user_name)
+    }
+
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
     pub fn process_command(&mut self, command_text: &str) -> Result<String, String> {
 
         debug!("Checking for command matching text \"{}\"", command_text);
 
-        for handler in &self.command_handlers {
-            if handler.matches_command_text(command_text) {
-                return handler.execute_command(command_text, &mut self.employee_store)
+        if let Some(result) = self.process_alias_command(command_text) {
+            return result;
+        }
+
+        // A macro alias runs each of its commands in turn, stopping at the first one that fails
+        let mut messages = vec![];
+        for expanded_command in self.aliases.expand(command_text)? {
+            messages.push(self.dispatch_to_handler(&expanded_command)?);
+        }
+        Ok(messages.join("\n"))
+    }
+
+    fn process_alias_command(&mut self, command_text: &str) -> Option<Result<String, String>> {
+        if let Some(captures) = Regex::new(&text("define_alias.pattern")).unwrap().captures(command_text) {
+            if let Err(msg) = self.access.check_permission(Role::Editor, command_text) {
+                return Some(Err(msg));
+            }
+            let reserved_words = reserved_words(&self.command_handlers);
+            if self.dry_run {
+                return Some(Ok(dry_run_message(message(
+                    "define_alias.would_define", &[("name", &&captures["name"]), ("template", &&captures["template"])]
+                ))));
+            }
+            return Some(self.aliases.define(&captures["name"], &captures["template"], &reserved_words));
+        }
+        if let Some(captures) = Regex::new(&text("remove_alias.pattern")).unwrap().captures(command_text) {
+            if let Err(msg) = self.access.check_permission(Role::Editor, command_text) {
+                return Some(Err(msg));
+            }
+            if self.dry_run {
+                return Some(Ok(dry_run_message(message("remove_alias.would_remove", &[("name", &&captures["name"])]))));
+            }
+            return Some(self.aliases.remove(&captures["name"]));
+        }
+        if Regex::new(&text("list_aliases.pattern")).unwrap().is_match(command_text) {
+            if let Err(msg) = self.access.check_permission(Role::Viewer, command_text) {
+                return Some(Err(msg));
+            }
+            let descriptions = self.aliases.describe_all();
+            for description in &descriptions {
+                info!("{}", description);
+            }
+            return Some(Ok(message("list_aliases.listed", &[("count", &descriptions.len())])));
+        }
+        None
+    }
+
+    fn dispatch_to_handler(&mut self, command_text: &str) -> Result<String, String> {
+        // Every handler is checked (rather than stopping at the first match) so that handler order
+        //  can't silently decide what an ambiguous command means.
+        let matching_handlers: Vec<&H> = self.command_handlers
+            .iter()
+            .filter(|handler| handler.matches_command_text(command_text))
+            .collect();
+
+        match matching_handlers.len() {
+            0 => Err(message("dispatcher.no_match", &[("command", &command_text)])),
+            1 => {
+                let handler = matching_handlers[0];
+                self.access.check_permission(handler.required_role(), command_text)?;
+                // Viewer commands change nothing, so run as normal even in a dry run
+                if self.dry_run && handler.required_role() >= Role::Editor {
+                    let preview = handler.preview_command(command_text, &self.employee_store)?;
+                    return Ok(dry_run_message(preview));
+                }
+                if handler.requires_confirmation() {
+                    // A preview error (e.g. no such department) is reported without asking for confirmation
+                    let preview = handler.preview_command(command_text, &self.employee_store)?;
+                    let prompt = format!("{}\n{}", preview, text("dispatcher.confirm"));
+                    if !self.confirmation.confirm(&prompt) {
+                        return Err(message("dispatcher.not_confirmed", &[("command", &command_text)]));
+                    }
+                }
+                let result = handler.execute_command(command_text, &mut self.employee_store);
+                // A change the store couldn't save is only made in memory, so would be lost without the user knowing
+                match self.employee_store.flush() {
+                    Ok(()) => result,
+                    Err(e) => Err(message("dispatcher.not_saved", &[("error", &e)])),
+                }
+            }
+            _ => {
+                let interpretations: Vec<String> = matching_handlers
+                    .iter()
+                    .map(|handler| format!(" - {}", handler.interpret(command_text)))
+                    .collect();
+                Err(format!(
+                    "{}\n{}", message("dispatcher.ambiguous", &[("command", &command_text)]), interpretations.join("\n")
+                ))
+            }
+        }
+    }
+
+    // Builds example commands for each handler by filling its placeholders with fragments of every handler's
+    //  description (e.g. "department X" or "X from X"), then reports any example that more than one handler matches.
+    pub fn find_ambiguities(&self) -> Vec<String> {
+        let placeholder_regex = Regex::new(PLACEHOLDER_PATTERN).unwrap();
+        let descriptions: Vec<String> = self.command_handlers.iter().map(|handler| handler.describe()).collect();
+
+        let mut fillers = vec![AMBIGUITY_CHECK_FILLER.to_string()];
+        for description in &descriptions {
+            let words: Vec<String> = placeholder_regex
+                .replace_all(description, AMBIGUITY_CHECK_FILLER)
+                .split_whitespace()
+                .map(|word| word.to_string())
+                .collect();
+            for start in 1..words.len() {
+                let filler = words[start..].join(" ");
+                if !fillers.contains(&filler) {
+                    fillers.push(filler);
+                }
             }
         }
 
-        Err(format!("No matching handler found for command \"{}\"", command_text))
+        let mut ambiguities: Vec<String> = vec![];
+        let mut reported_handler_sets: Vec<Vec<usize>> = vec![];
+        for description in &descriptions {
+            let placeholder_count = placeholder_regex.find_iter(description).count();
+            for varied_placeholder in 0..placeholder_count {
+                for filler in &fillers {
+                    let mut placeholder_index = 0;
+                    let example = placeholder_regex.replace_all(description, |_: &regex::Captures| {
+                        placeholder_index += 1;
+                        if placeholder_index - 1 == varied_placeholder { filler.clone() }
+                        else { AMBIGUITY_CHECK_FILLER.to_string() }
+                    });
+                    // Matching normally logs every attempt, which would swamp the log for this many examples
+                    let matching: Vec<usize> = self.command_handlers
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, handler)| handler.matches_command_text_quietly(&example))
+                        .map(|(index, _)| index)
+                        .collect();
+                    if matching.len() > 1 && !reported_handler_sets.contains(&matching) {
+                        let matching_descriptions: Vec<String> = matching
+                            .iter()
+                            .map(|index| format!("\"{}\"", descriptions[*index]))
+                            .collect();
+                        ambiguities.push(format!(
+                            "Command formats {} all match commands such as \"{}\"",
+                            matching_descriptions.join(", "), example
+                        ));
+                        reported_handler_sets.push(matching);
+                    }
+                }
+            }
+        }
+
+        ambiguities
     }
 
     pub fn get_usage_text(&self) -> String {
-        let mut text = "Employee Management - valid command formats:\n".to_string();
+        let mut usage_text = format!("{}\n", text("dispatcher.usage_heading"));
         for handler in &self.command_handlers {
-            text += &format!(" - \"{}\"\n", &handler.describe());
+            usage_text += &format!(" - \"{}\"\n", &handler.describe());
         }
-        text
+        usage_text
+    }
+
+    pub fn get_alias_usage_text(&self) -> String {
+        let mut usage_text = format!("{}\n", text("alias_usage.heading"));
+        usage_text += &format!(" - \"{}\" {}\n", text("define_alias.description"), text("alias_usage.example"));
+        usage_text += &format!(" - \"{}\"\n", text("remove_alias.description"));
+        usage_text += &format!(" - \"{}\"\n", text("list_aliases.description"));
+        usage_text
     }
 
 }
@@ -40,16 +234,27 @@ impl<E: 'static + EmployeeStore, H: HandleCommand<E>> CommandDispatcher<E, H> {
 
 #[cfg(test)]
 mod tests {
-    use super::CommandDispatcher;
-    use crate::employee_store::{MockEmployeeStore, EmployeeStoreImpl};
+    use super::{create_dispatcher, CommandDispatcher};
+    use crate::access::{AccessControl, Role};
+    use crate::command::alias::AliasRegistry;
+    use crate::command::confirmation::MockConfirm;
+    use crate::employee_store::{EmployeeStore, MockEmployeeStore, EmployeeStoreImpl};
+    use crate::locale::set_language;
     use log::Level::Debug;
     use mockall::predicate::eq;
+    use std::collections::BTreeMap;
     use crate::command::MockHandleCommand;
 
     static COMMAND: &str = "Some command";
 
     type MockHandler = MockHandleCommand<EmployeeStoreImpl>;
 
+    fn dispatcher_for<S: 'static + EmployeeStore>(command_handlers: Vec<MockHandleCommand<S>>, employee_store: S)
+        -> CommandDispatcher<S, MockHandleCommand<S>>
+    {
+        create_dispatcher(command_handlers, employee_store, AliasRegistry::new(), AccessControl::default())
+    }
+
     impl MockHandler {
 
         fn with_match_called_expectation(mut self, return_value: bool) -> MockHandler {
@@ -62,6 +267,12 @@ mod tests {
         }
 
         fn with_execute_called_expectation(mut self, return_value: Result<String, String>) -> MockHandler {
+            self
+                .expect_required_role()
+                .return_const(Role::Viewer);
+            self
+                .expect_requires_confirmation()
+                .return_const(false);
             self
                 .expect_execute_command()
                 .times(1)
@@ -88,7 +299,7 @@ mod tests {
 
     fn run_test(command_handlers: Vec<MockHandler>, expected_result: Result<String, String>) {
         testing_logger::setup();
-        let mut dispatcher = CommandDispatcher { command_handlers, employee_store: EmployeeStoreImpl::new(), };
+        let mut dispatcher = dispatcher_for(command_handlers, EmployeeStoreImpl::new());
         assert_eq!(dispatcher.process_command(COMMAND), expected_result);
         testing_logger::validate(|captured_logs| {
             assert_eq!(captured_logs.len(), 1);
@@ -104,15 +315,76 @@ mod tests {
     }
 
     #[test]
-    fn test_stops_on_first_matching_handler() {
+    fn test_calls_only_matching_handler() {
         let command_handlers = vec![
             handler_non_match(),
             handler_match_expect_executor_called(),
-            MockHandler::new(),     // Does not expect to be called
+            handler_non_match(),
         ];
         run_test(command_handlers, get_success_result());
     }
 
+    #[test]
+    fn test_reports_ambiguity_without_executing_when_several_handlers_match() {
+        fn ambiguous_handler(interpretation: &str) -> MockHandler {
+            let mut handler = MockHandleCommand::new().with_match_called_expectation(true);
+            handler.expect_execute_command().times(0);
+            handler.expect_interpret().with(eq(COMMAND)).return_const(interpretation.to_string());
+            handler
+        }
+
+        let mut dispatcher = dispatcher_for(
+            vec![ambiguous_handler("Interpretation 1"), handler_non_match(), ambiguous_handler("Interpretation 2")],
+            EmployeeStoreImpl::new(),
+        );
+
+        assert_eq!(
+            dispatcher.process_command(COMMAND),
+            Err(
+                "Command \"Some command\" is ambiguous and has not been run - it could mean:\n".to_string() +
+                    " - Interpretation 1\n - Interpretation 2"
+            )
+        );
+    }
+
+    #[test]
+    fn test_find_ambiguities_detects_overlapping_real_handlers() {
+        let dispatcher = create_dispatcher(
+            crate::command::handler::get_all_handlers(),
+            EmployeeStoreImpl::new(),
+            AliasRegistry::new(),
+            AccessControl::default(),
+        );
+        testing_logger::setup();
+        let ambiguities = dispatcher.find_ambiguities();
+        assert!(ambiguities.contains(&"Command formats \"Delete department (department name)\", \
+            \"Delete (employee name) from (department name)\" all match commands such as \"Delete department X from X\""
+            .to_string()), "{:?}", ambiguities);
+        testing_logger::validate(|captured_logs| assert!(captured_logs.is_empty(), "{} logs", captured_logs.len()));
+    }
+
+    #[test]
+    fn test_real_handlers_use_vocabulary_of_chosen_language() {
+        set_language("fr").unwrap();
+        let mut dispatcher = create_dispatcher(
+            crate::command::handler::get_all_handlers(),
+            EmployeeStoreImpl::new(),
+            AliasRegistry::new(),
+            AccessControl::default(),
+        );
+        let added = dispatcher.process_command("Ajouter Bob à Contrôle qualité");
+        let english_command = dispatcher.process_command("Add Bob to Pie QC");
+        let ambiguities = dispatcher.find_ambiguities();
+        set_language("en").unwrap();
+
+        assert_eq!(added, Ok("Employé \"Bob\" ajouté au service \"Contrôle qualité\"".to_string()));
+        assert_eq!(english_command, Err("Aucune commande ne correspond à \"Add Bob to Pie QC\"".to_string()));
+        assert!(
+            ambiguities.iter().any(|ambiguity| ambiguity.contains("\"Supprimer le service X de X\"")),
+            "{:?}", ambiguities
+        );
+    }
+
     #[test]
     fn test_returns_expected_result_for_no_matching_handlers() {
         let command_handlers = vec![handler_non_match()];
@@ -138,10 +410,10 @@ mod tests {
             handler
         }
 
-        let dispatcher = CommandDispatcher {
-            command_handlers: vec![mock_handler("Description 1"), mock_handler("Description 2")],
-            employee_store: MockEmployeeStore::new()
-        };
+        let dispatcher = dispatcher_for(
+            vec![mock_handler("Description 1"), mock_handler("Description 2")],
+            MockEmployeeStore::new(),
+        );
 
         let expected_text = "Employee Management - valid command formats:\n".to_string() +
             " - \"Description 1\"\n - \"Description 2\"\n";
@@ -149,4 +421,229 @@ mod tests {
         assert_eq!(dispatcher.get_usage_text(), expected_text);
     }
 
+    fn handler_expecting_execution_of(command_text: &'static str) -> MockHandler {
+        let mut handler = MockHandleCommand::new();
+        handler
+            .expect_matches_command_text()
+            .with(eq(command_text))
+            .return_const(true);
+        handler
+            .expect_required_role()
+            .return_const(Role::Editor);
+        handler
+            .expect_requires_confirmation()
+            .return_const(false);
+        handler
+            .expect_execute_command()
+            .times(1)
+            .with(eq(command_text), eq(EmployeeStoreImpl::new()))
+            .return_const(Ok(format!("Ran \"{}\"", command_text)));
+        handler
+    }
+
+    fn dispatcher_with_handler(handler: MockHandler) -> CommandDispatcher<EmployeeStoreImpl, MockHandler> {
+        dispatcher_for(vec![handler], EmployeeStoreImpl::new())
+    }
+
+    #[test]
+    fn test_defines_and_expands_alias_before_matching_handlers() {
+        let mut handler = handler_expecting_execution_of("Add Bob to Pie QC");
+        handler.expect_describe().return_const("Add (employee name) to (department name)".to_string());
+        let mut dispatcher = dispatcher_with_handler(handler);
+
+        assert_eq!(
+            dispatcher.process_command("Alias hire = Add {1} to {2}"),
+            Ok("Successfully defined alias \"hire\" as \"Add {1} to {2}\"".to_string())
+        );
+        assert_eq!(dispatcher.process_command("hire Bob \"Pie QC\""), Ok("Ran \"Add Bob to Pie QC\"".to_string()));
+    }
+
+    #[test]
+    fn test_macro_alias_runs_each_command() {
+        let mut handler = MockHandleCommand::new();
+        handler
+            .expect_matches_command_text()
+            .with(eq("Run it"))
+            .return_const(true);
+        handler
+            .expect_required_role()
+            .return_const(Role::Editor);
+        handler
+            .expect_requires_confirmation()
+            .return_const(false);
+        handler
+            .expect_execute_command()
+            .times(2)
+            .with(eq("Run it"), eq(EmployeeStoreImpl::new()))
+            .return_const(Ok("Ran it".to_string()));
+        let mut dispatcher = dispatcher_with_handler(handler);
+        dispatcher.aliases.define("twice", "Run {1}; Run {1}", &[]).unwrap();
+
+        assert_eq!(dispatcher.process_command("twice it"), Ok("Ran it\nRan it".to_string()));
+    }
+
+    #[test]
+    fn test_alias_cannot_shadow_command() {
+        let mut handler = MockHandleCommand::new();
+        handler.expect_describe().return_const("Add (employee name) to (department name)".to_string());
+        let mut dispatcher = dispatcher_with_handler(handler);
+
+        assert_eq!(
+            dispatcher.process_command("Alias Add = List departments"),
+            Err("Alias name \"Add\" would hide an existing command".to_string())
+        );
+    }
+
+    fn destructive_handler(expected_executions: usize) -> MockHandler {
+        let mut handler = MockHandleCommand::new().with_match_called_expectation(true);
+        handler.expect_required_role().return_const(Role::Editor);
+        handler.expect_requires_confirmation().return_const(true);
+        handler.expect_preview_command().return_const(Ok("Would drop everything".to_string()));
+        handler
+            .expect_execute_command()
+            .times(expected_executions)
+            .return_const(Ok("Dropped everything".to_string()));
+        handler
+    }
+
+    fn confirmation_returning(answer: bool) -> Box<MockConfirm> {
+        let mut confirmation = MockConfirm::new();
+        confirmation
+            .expect_confirm()
+            .times(1)
+            .with(eq("Would drop everything\nThis cannot easily be undone - are you sure?"))
+            .return_const(answer);
+        Box::new(confirmation)
+    }
+
+    #[test]
+    fn test_destructive_command_runs_once_confirmed() {
+        let mut dispatcher = dispatcher_with_handler(destructive_handler(1));
+        dispatcher.confirmation = confirmation_returning(true);
+        assert_eq!(dispatcher.process_command(COMMAND), Ok("Dropped everything".to_string()));
+    }
+
+    #[test]
+    fn test_destructive_command_not_run_unless_confirmed() {
+        let mut dispatcher = dispatcher_with_handler(destructive_handler(0));
+        dispatcher.confirmation = confirmation_returning(false);
+        assert_eq!(
+            dispatcher.process_command(COMMAND),
+            Err("Command \"Some command\" was not confirmed and has not been run".to_string())
+        );
+    }
+
+    #[test]
+    fn test_destructive_command_not_confirmed_if_preview_fails() {
+        let mut handler = MockHandleCommand::new().with_match_called_expectation(true);
+        handler.expect_required_role().return_const(Role::Admin);
+        handler.expect_requires_confirmation().return_const(true);
+        handler.expect_preview_command().return_const(Err("Department \"Pies\" does not exist".to_string()));
+        handler.expect_execute_command().times(0);
+        let mut dispatcher = dispatcher_with_handler(handler);
+        let mut confirmation = MockConfirm::new();
+        confirmation.expect_confirm().times(0);
+        dispatcher.confirmation = Box::new(confirmation);
+
+        assert_eq!(dispatcher.process_command(COMMAND), Err("Department \"Pies\" does not exist".to_string()));
+    }
+
+    #[test]
+    fn test_reports_changes_the_store_could_not_save() {
+        let mut handler: MockHandleCommand<MockEmployeeStore> = MockHandleCommand::new();
+        handler.expect_matches_command_text().return_const(true);
+        handler.expect_required_role().return_const(Role::Editor);
+        handler.expect_requires_confirmation().return_const(false);
+        handler.expect_execute_command().times(1).return_const(Ok("Added Bob".to_string()));
+        let mut store = MockEmployeeStore::new();
+        store
+            .expect_flush()
+            .times(1)
+            .return_const(Err("Could not save roster to \"roster.json\" - disk full".to_string()));
+        let mut dispatcher = dispatcher_for(vec![handler], store);
+
+        assert_eq!(
+            dispatcher.process_command(COMMAND),
+            Err(
+                "The change has been made, but will be lost on exit as it could not be saved - Could not save roster \
+                to \"roster.json\" - disk full".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_command_not_requiring_confirmation_runs_without_asking_whatever_its_role() {
+        let mut handler = MockHandleCommand::new().with_match_called_expectation(true);
+        handler.expect_required_role().return_const(Role::Admin);
+        handler.expect_requires_confirmation().return_const(false);
+        handler.expect_preview_command().times(0);
+        handler.expect_execute_command().times(1).return_const(Ok("Done".to_string()));
+        let mut dispatcher = dispatcher_with_handler(handler);
+        let mut confirmation = MockConfirm::new();
+        confirmation.expect_confirm().times(0);
+        dispatcher.confirmation = Box::new(confirmation);
+
+        assert_eq!(dispatcher.process_command(COMMAND), Ok("Done".to_string()));
+    }
+
+    #[test]
+    fn test_dry_run_previews_mutating_command_without_running_it() {
+        let mut handler = MockHandleCommand::new().with_match_called_expectation(true);
+        handler.expect_required_role().return_const(Role::Editor);
+        handler
+            .expect_preview_command()
+            .times(1)
+            .with(eq(COMMAND), eq(EmployeeStoreImpl::new()))
+            .return_const(Ok("Would add Bob".to_string()));
+        handler.expect_execute_command().times(0);
+        let mut dispatcher = dispatcher_with_handler(handler);
+        dispatcher.set_dry_run(true);
+
+        assert_eq!(dispatcher.process_command(COMMAND), Ok("[dry run] Would add Bob".to_string()));
+    }
+
+    #[test]
+    fn test_dry_run_still_runs_viewer_commands() {
+        let handler = handler_match_expect_executor_called();
+        let mut dispatcher = dispatcher_with_handler(handler);
+        dispatcher.set_dry_run(true);
+
+        assert_eq!(dispatcher.process_command(COMMAND), get_success_result());
+    }
+
+    #[test]
+    fn test_dry_run_does_not_define_alias() {
+        let mut handler = MockHandleCommand::new();
+        handler.expect_describe().return_const("Add (employee name) to (department name)".to_string());
+        let mut dispatcher = dispatcher_with_handler(handler);
+        dispatcher.set_dry_run(true);
+
+        assert_eq!(
+            dispatcher.process_command("Alias hire = Add {1} to {2}"),
+            Ok("[dry run] Would define alias \"hire\" as \"Add {1} to {2}\"".to_string())
+        );
+        assert_eq!(dispatcher.aliases.describe_all().len(), 0);
+    }
+
+    #[test]
+    fn test_command_not_run_without_required_role() {
+        let mut handler = MockHandleCommand::new();
+        handler.expect_matches_command_text().times(2).return_const(true);
+        handler.expect_required_role().return_const(Role::Admin);
+        handler.expect_execute_command().times(0);
+        let mut dispatcher = dispatcher_with_handler(handler);
+        let mut users = BTreeMap::new();
+        users.insert("ed".to_string(), Role::Editor);
+        dispatcher.access = AccessControl::new(users);
+
+        assert_eq!(
+            dispatcher.process_command(COMMAND),
+            Err("Must be logged in to run \"Some command\"".to_string())
+        );
+        dispatcher.log_in("ed").unwrap();
+        assert_eq!(
+            dispatcher.process_command(COMMAND),
+            Err("User \"ed\" (role editor) is not permitted to run \"Some command\" - requires role admin".to_string())
+        );
+    }
 }
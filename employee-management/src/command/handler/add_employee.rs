@@ -1,40 +1,57 @@
-use super::{ParsedArgMap, CommandHandler, CommandExecutor};
-use crate::employee_store::EmployeeStore;
+use super::{ParsedArgMap, CommandHandler, CommandExecutor, CommandPreviewer};
+use crate::employee_store::{EmployeeStore, EmptyDepartmentPolicy};
+use crate::locale::{message, text};
 use regex::Regex;
 
-const MATCH_PATTERN_DESCRIPTION: &'static str = "Add (employee name) to (department name)";
-const REGEX_PATTERN: &'static str = r"^Add (?P<employee_name>.*) to (?P<department>.*)$";
+// Shared by the executor and previewer so both give the same errors - on success, says whether the department exists
+fn check_can_add<E: EmployeeStore>(store: &E, employee_name: &str, department: &str) -> Result<bool, String> {
+    match store
+        .retrieve_employees_by_department(&department.to_string())
+        .map(|employees| employees.iter().any(|e| e == employee_name))
+    {
+        // This check could in some cases be handled as (e.g.) a unique constraint on underlying data store,
+        // but let's assume we want to do this as a business logic concern in this layer independent of storage impl
+        Some(true) => {
+            Err(message(
+                "add_employee.already_exists", &[("employee_name", &employee_name), ("department", &department)]
+            ))
+        },
+        None if store.get_empty_department_policy() == EmptyDepartmentPolicy::RequireExplicitCreate => {
+            Err(message("add_employee.department_missing", &[("department", &department)]))
+        },
+        Some(false) => Ok(true),
+        None => Ok(false),
+    }
+}
 
 pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
     let executor: CommandExecutor<E> = |arg_map: ParsedArgMap, store: &mut E| {
         let employee_name = arg_map.get("employee_name").unwrap();
         let department = arg_map.get("department").unwrap();
-        match store
-            .retrieve_employees_by_department(department)
-            .map(|employees| employees.iter().any(|e| e == employee_name))
-        {
-            // This check could in some cases be handled as (e.g.) a unique constraint on underlying data store,
-            // but let's assume we want to do this as a business logic concern in this layer independent of storage impl
-            Some(true) => {
-                Err(format!(
-                    "Employee \"{}\" already exists in department \"{}\" and cannot be added", employee_name, department)
-                )
-            },
-            _ => {
-                // TODO - if this were connecting to an actual database it would be able to error -
-                //  do we want to allow for that case?
-                store.add_employee(employee_name, department);
-                Ok(format!("Successfully added employee \"{}\" to department \"{}\"", employee_name, department))
-            }
-        }
+        check_can_add(store, employee_name, department)?;
+        // TODO - if this were connecting to an actual database it would be able to error -
+        //  do we want to allow for that case?
+        store.add_employee(employee_name, department);
+        Ok(message("add_employee.added", &[("employee_name", employee_name), ("department", department)]))
+    };
+
+    let previewer: CommandPreviewer<E> = |arg_map: ParsedArgMap, store: &E| {
+        let employee_name = arg_map.get("employee_name").unwrap();
+        let department = arg_map.get("department").unwrap();
+        let key = if check_can_add(store, employee_name, department)? {
+            "add_employee.would_add"
+        } else {
+            "add_employee.would_add_creating_department"
+        };
+        Ok(message(key, &[("employee_name", employee_name), ("department", department)]))
     };
 
     CommandHandler::new(
-        MATCH_PATTERN_DESCRIPTION,
-        Regex::new(REGEX_PATTERN).unwrap(),
+        text("add_employee.description"),
+        Regex::new(&text("add_employee.pattern")).unwrap(),
         vec!["employee_name", "department"],
         executor,
-    )
+    ).with_previewer(previewer)
 }
 
 
@@ -44,7 +61,7 @@ mod tests {
     use crate::command::HandleCommand;
     use crate::command::handler::CommandHandler;
     use mockall::predicate::eq;
-    use crate::employee_store::MockEmployeeStore;
+    use crate::employee_store::{MockEmployeeStore, EmptyDepartmentPolicy};
 
     const MATCHING_COMMAND: &str = "Add Bob to Pie QC";
     const NON_MATCHING_COMMAND: &'static str = "Add Bob into the Pie Eating department";
@@ -72,6 +89,9 @@ mod tests {
             .times(1)
             .with(eq(String::from("Pie QC")))
             .return_const(None);
+        mock_store
+            .expect_get_empty_department_policy()
+            .return_const(EmptyDepartmentPolicy::Keep);
         mock_store
             .expect_add_employee()
             .times(1)
@@ -109,4 +129,87 @@ mod tests {
             Err("Employee \"Bob\" already exists in department \"Pie QC\" and cannot be added".to_string())
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_executor_errors_without_adding_to_missing_department_if_explicit_create_required() {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_retrieve_employees_by_department()
+            .times(1)
+            .with(eq("Pie QC".to_string()))
+            .return_const(None);
+        mock_store
+            .expect_get_empty_department_policy()
+            .return_const(EmptyDepartmentPolicy::RequireExplicitCreate);
+        mock_store
+            .expect_add_employee()
+            .times(0);
+
+        let result = get_handler()
+            .execute_command(MATCHING_COMMAND, &mut mock_store);
+
+        assert_eq!(
+            result,
+            Err("Department \"Pie QC\" does not exist - create it with \"Create department Pie QC\" before adding employees".to_string())
+        );
+    }
+
+    fn run_test_call_previewer(
+        existing_employees: Option<Vec<String>>,
+        policy: EmptyDepartmentPolicy,
+        expected_handler_result: Result<String, String>,
+    ) {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_retrieve_employees_by_department()
+            .times(1)
+            .with(eq("Pie QC".to_string()))
+            .return_const(existing_employees);
+        mock_store
+            .expect_get_empty_department_policy()
+            .return_const(policy);
+        mock_store
+            .expect_add_employee()
+            .times(0);
+
+        let result = get_handler()
+            .preview_command(MATCHING_COMMAND, &mock_store);
+
+        assert_eq!(result, expected_handler_result);
+    }
+
+    #[test]
+    fn test_previews_adding_to_existing_department() {
+        run_test_call_previewer(
+            Some(vec!["Weebl".to_string()]),
+            EmptyDepartmentPolicy::Keep,
+            Ok("Would add employee \"Bob\" to department \"Pie QC\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_previews_adding_to_new_department() {
+        run_test_call_previewer(
+            None,
+            EmptyDepartmentPolicy::AutoDelete,
+            Ok("Would add employee \"Bob\" to new department \"Pie QC\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_preview_gives_executor_errors() {
+        run_test_call_previewer(
+            Some(vec!["Bob".to_string()]),
+            EmptyDepartmentPolicy::Keep,
+            Err("Employee \"Bob\" already exists in department \"Pie QC\" and cannot be added".to_string())
+        );
+        run_test_call_previewer(
+            None,
+            EmptyDepartmentPolicy::RequireExplicitCreate,
+            Err(
+                "Department \"Pie QC\" does not exist - create it with \"Create department Pie QC\" before adding \
+                employees".to_string()
+            )
+        );
+    }
+}
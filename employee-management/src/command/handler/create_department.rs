@@ -0,0 +1,113 @@
+use super::{ParsedArgMap, CommandHandler, CommandExecutor, CommandPreviewer};
+use crate::employee_store::EmployeeStore;
+use crate::locale::{message, text};
+use regex::Regex;
+
+pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
+    let executor: CommandExecutor<E> = |arg_map: ParsedArgMap, store: &mut E| {
+        let department = arg_map.get("department").unwrap();
+        info!("Creating department \"{}\"", department);
+        store.create_department(department).map(
+            |_| message("create_department.created", &[("department", department)])
+        )
+    };
+
+    let previewer: CommandPreviewer<E> = |arg_map: ParsedArgMap, store: &E| {
+        let department = arg_map.get("department").unwrap();
+        match store.retrieve_employees_by_department(department) {
+            Some(_) => Err(message("create_department.already_exists", &[("department", department)])),
+            None => Ok(message("create_department.would_create", &[("department", department)]))
+        }
+    };
+
+    CommandHandler::new(
+        text("create_department.description"),
+        Regex::new(&text("create_department.pattern")).unwrap(),
+        vec!["department"],
+        executor,
+    ).with_previewer(previewer)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::get_handler;
+    use crate::command::HandleCommand;
+    use crate::command::handler::CommandHandler;
+    use mockall::predicate::eq;
+    use crate::employee_store::MockEmployeeStore;
+
+    const MATCHING_COMMAND: &str = "Create department Pie QC";
+    const NON_MATCHING_COMMAND: &'static str = "We need a Pie QC department";
+
+    fn run_test_against_matcher(command_text: &str, expected_return: bool) {
+        let test_handler: CommandHandler<MockEmployeeStore> = get_handler();
+        assert_eq!(test_handler.matches_command_text(command_text), expected_return)
+    }
+
+    #[test]
+    fn test_matcher_handles_matching_pattern() {
+        run_test_against_matcher(MATCHING_COMMAND, true);
+    }
+
+    #[test]
+    fn test_matcher_handles_non_matching_pattern() {
+        run_test_against_matcher(NON_MATCHING_COMMAND, false);
+    }
+
+    fn run_test_call_executor(mock_return_value: Result<(), String>, expected_handler_result: Result<String, String>) {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_create_department()
+            .times(1)
+            .with(eq("Pie QC".to_string()))
+            .return_once(move |_dept| mock_return_value);
+
+        let result = get_handler()
+            .execute_command(MATCHING_COMMAND, &mut mock_store);
+
+        assert_eq!(result, expected_handler_result);
+    }
+
+    #[test]
+    fn test_handles_successful_creation() {
+        run_test_call_executor(Ok(()), Ok("Successfully created department \"Pie QC\"".to_string()));
+    }
+
+    #[test]
+    fn test_handles_failure_to_create_department() {
+        run_test_call_executor(
+            Err("Something bad happened".to_string()),
+            Err("Something bad happened".to_string())
+        );
+    }
+
+    fn run_test_call_previewer(
+        mock_return_value: Option<Vec<String>>, expected_handler_result: Result<String, String>
+    ) {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_retrieve_employees_by_department()
+            .times(1)
+            .with(eq("Pie QC".to_string()))
+            .return_once(move |_dept| mock_return_value);
+
+        let result = get_handler()
+            .preview_command(MATCHING_COMMAND, &mock_store);
+
+        assert_eq!(result, expected_handler_result);
+    }
+
+    #[test]
+    fn test_previews_creation() {
+        run_test_call_previewer(None, Ok("Would create department \"Pie QC\"".to_string()));
+    }
+
+    #[test]
+    fn test_preview_fails_for_existing_department() {
+        run_test_call_previewer(
+            Some(vec![]),
+            Err("Could not create department \"Pie QC\" - department already exists".to_string())
+        );
+    }
+}
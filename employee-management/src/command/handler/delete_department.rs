@@ -1,29 +1,44 @@
-use crate::command::handler::{CommandHandler, CommandExecutor, ParsedArgMap};
-use crate::employee_store::EmployeeStore;
+use crate::access::Role;
+use crate::command::handler::{CommandHandler, CommandExecutor, CommandPreviewer, ParsedArgMap};
+use crate::employee_store::{EmployeeStore, DepartmentInfo};
+use crate::locale::{message, text};
 use regex:: Regex;
 
-const MATCH_PATTERN_DESCRIPTION: &'static str = "Delete department (department name)";
-const REGEX_PATTERN: &'static str = r"^Delete department (?P<department>.*)$";
-
 pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
     let executor: CommandExecutor<E> = |arg_map: ParsedArgMap, store: &mut E| {
         let department = arg_map.get("department").unwrap();
         info!("Deleting department \"{}\"", department);
         store.delete_department(department).map(
             |dept_info|
-                format!(
-                    "Department deleted successfully - \"{}\" (employees {})",
-                    department, dept_info.employee_names.join(", ")
+                message(
+                    "delete_department.deleted",
+                    &[("department", department), ("employees", &dept_info.employee_names.join(", "))]
                 )
         )
     };
 
-    CommandHandler {
-        match_pattern_description: MATCH_PATTERN_DESCRIPTION,
-        matcher_regex: Regex::new(REGEX_PATTERN).unwrap(),
-        expected_args: vec!["department".to_string()],
-        executor
-    }
+    // Builds the DepartmentInfo the deletion would return, without deleting anything
+    let previewer: CommandPreviewer<E> = |arg_map: ParsedArgMap, store: &E| {
+        let department = arg_map.get("department").unwrap();
+        match store.retrieve_employees_by_department(department) {
+            Some(employee_names) => {
+                let dept_info = DepartmentInfo { department: department.clone(), employee_names };
+                Ok(message("delete_department.would_delete", &[
+                    ("department", &dept_info.department),
+                    ("count", &dept_info.employee_names.len()),
+                    ("employees", &dept_info.employee_names.join(", ")),
+                ]))
+            }
+            None => Err(message("department_does_not_exist", &[("department", department)]))
+        }
+    };
+
+    CommandHandler::new(
+        text("delete_department.description"),
+        Regex::new(&text("delete_department.pattern")).unwrap(),
+        vec!["department"],
+        executor,
+    ).with_required_role(Role::Admin).with_previewer(previewer).with_confirmation_required()
 }
 
 
@@ -91,4 +106,33 @@ mod tests {
         );
     }
 
+    fn run_test_call_previewer(
+        mock_return_value: Option<Vec<String>>, expected_handler_result: Result<String, String>
+    ) {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_retrieve_employees_by_department()
+            .times(1)
+            .with(eq("Pie Eating".to_string()))
+            .return_once(move |_dept| mock_return_value);
+
+        let result = get_handler()
+            .preview_command(MATCHING_COMMAND, &mock_store);
+
+        assert_eq!(result, expected_handler_result);
+    }
+
+    #[test]
+    fn test_previews_deletion_with_employees() {
+        run_test_call_previewer(
+            Some(vec!["Bob".to_string(), "Weebl".to_string()]),
+            Ok("Would delete department \"Pie Eating\" and its 2 employees (Bob, Weebl)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_preview_fails_for_non_existent_department() {
+        run_test_call_previewer(None, Err("Department \"Pie Eating\" does not exist".to_string()));
+    }
+
 }
\ No newline at end of file
@@ -1,13 +1,11 @@
 use super::{ParsedArgMap, CommandHandler};
-use crate::employee_store::EmployeeStore;
+use crate::employee_store::{EmployeeStore, EmptyDepartmentPolicy};
 use crate::employee_store::EmployeeDeletionResult::{
     SuccessfullyDeleted, NoSuchDepartment, EmployeeNotInDepartment,
 };
 use regex::Regex;
-use crate::command::handler::CommandExecutor;
-
-const MATCH_PATTERN_DESCRIPTION: &'static str = "Delete (employee name) from (department name)";
-const REGEX_PATTERN: &'static str = r"^Delete (?P<employee_name>.*) from (?P<department>.*)$";
+use crate::command::handler::{CommandExecutor, CommandPreviewer};
+use crate::locale::{message, text};
 
 pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
 
@@ -17,23 +15,51 @@ pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
 
         match store.delete_employee(employee_name, department) {
             NoSuchDepartment => {
-                Err(format!("Department \"{}\" does not exist", department))
+                Err(message("department_does_not_exist", &[("department", department)]))
             }
             EmployeeNotInDepartment => {
-                Err(format!("Employee \"{}\" does not exist in department \"{}\"", employee_name, department))
+                Err(message(
+                    "delete_employee.not_in_department", &[("employee_name", employee_name), ("department", department)]
+                ))
             }
             SuccessfullyDeleted => {
-                Ok(format!("Successfully deleted employee \"{}\" from department \"{}\"", employee_name, department))
+                Ok(message("delete_employee.deleted", &[("employee_name", employee_name), ("department", department)]))
+            }
+        }
+    };
+
+    // Checks in the same order as the store's delete, so gives the same errors
+    let previewer: CommandPreviewer<E> = |arg_map: ParsedArgMap, store: &E| {
+        let employee_name = arg_map.get("employee_name").unwrap();
+        let department = arg_map.get("department").unwrap();
+        match store.retrieve_employees_by_department(department) {
+            None => {
+                Err(message("department_does_not_exist", &[("department", department)]))
+            }
+            Some(employee_names) if !employee_names.contains(employee_name) => {
+                Err(message(
+                    "delete_employee.not_in_department", &[("employee_name", employee_name), ("department", department)]
+                ))
+            }
+            Some(employee_names) => {
+                let empties_department = employee_names.len() == 1
+                    && store.get_empty_department_policy() == EmptyDepartmentPolicy::AutoDelete;
+                let key = if empties_department {
+                    "delete_employee.would_delete_removing_department"
+                } else {
+                    "delete_employee.would_delete"
+                };
+                Ok(message(key, &[("employee_name", employee_name), ("department", department)]))
             }
         }
     };
 
     CommandHandler::new(
-        MATCH_PATTERN_DESCRIPTION,
-        Regex::new(REGEX_PATTERN).unwrap(),
+        text("delete_employee.description"),
+        Regex::new(&text("delete_employee.pattern")).unwrap(),
         vec!["employee_name", "department"],
         executor,
-    )
+    ).with_previewer(previewer).with_confirmation_required()
 }
 
 
@@ -43,7 +69,7 @@ mod tests {
     use crate::command::HandleCommand;
     use crate::command::handler::CommandHandler;
     use mockall::predicate::eq;
-    use crate::employee_store::{MockEmployeeStore, EmployeeDeletionResult};
+    use crate::employee_store::{MockEmployeeStore, EmployeeDeletionResult, EmptyDepartmentPolicy};
     use crate::employee_store::EmployeeDeletionResult::{
         SuccessfullyDeleted, NoSuchDepartment, EmployeeNotInDepartment,
     };
@@ -107,4 +133,68 @@ mod tests {
             Err("Employee \"Bob\" does not exist in department \"Pie QC\"".to_string())
         );
     }
-}
\ No newline at end of file
+
+    fn run_previewer_call_test(
+        existing_employees: Option<Vec<&str>>,
+        policy: EmptyDepartmentPolicy,
+        expected_result: Result<String, String>,
+    ) {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_retrieve_employees_by_department()
+            .times(1)
+            .with(eq(String::from("Pie QC")))
+            .return_const(existing_employees.map(|names| names.iter().map(|n| n.to_string()).collect::<Vec<_>>()));
+        mock_store
+            .expect_get_empty_department_policy()
+            .return_const(policy);
+        mock_store
+            .expect_delete_employee()
+            .times(0);
+
+        let result = get_handler()
+            .preview_command(MATCHING_COMMAND, &mock_store);
+
+        assert_eq!(result, expected_result);
+    }
+
+    #[test]
+    fn test_previews_deletion() {
+        run_previewer_call_test(
+            Some(vec!["Bob", "Weebl"]),
+            EmptyDepartmentPolicy::AutoDelete,
+            Ok("Would delete employee \"Bob\" from department \"Pie QC\"".to_string()),
+        );
+        run_previewer_call_test(
+            Some(vec!["Bob"]),
+            EmptyDepartmentPolicy::Keep,
+            Ok("Would delete employee \"Bob\" from department \"Pie QC\"".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_previews_deletion_removing_emptied_department() {
+        run_previewer_call_test(
+            Some(vec!["Bob"]),
+            EmptyDepartmentPolicy::AutoDelete,
+            Ok(
+                "Would delete employee \"Bob\" from department \"Pie QC\", removing the department as it would be \
+                empty".to_string()
+            ),
+        );
+    }
+
+    #[test]
+    fn test_preview_gives_executor_errors() {
+        run_previewer_call_test(
+            None,
+            EmptyDepartmentPolicy::Keep,
+            Err("Department \"Pie QC\" does not exist".to_string())
+        );
+        run_previewer_call_test(
+            Some(vec!["Weebl"]),
+            EmptyDepartmentPolicy::Keep,
+            Err("Employee \"Bob\" does not exist in department \"Pie QC\"".to_string())
+        );
+    }
+}
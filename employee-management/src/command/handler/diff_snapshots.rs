@@ -0,0 +1,83 @@
+use super::{ParsedArgMap, CommandHandler, CommandExecutor};
+use crate::access::Role;
+use crate::employee_store::{DepartmentInfo, EmployeeStore};
+use crate::locale::{message, text};
+use crate::snapshot::{load_snapshot, snapshot_directory, RosterDiff, CURRENT_ROSTER_LABEL};
+use regex::Regex;
+
+fn load_roster<E: EmployeeStore>(label: &str, store: &E) -> Result<Vec<DepartmentInfo>, String> {
+    if label == CURRENT_ROSTER_LABEL {
+        Ok(store.retrieve_all_employees())
+    } else {
+        load_snapshot(&snapshot_directory(), label)
+    }
+}
+
+pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
+    let executor: CommandExecutor<E> = |arg_map: ParsedArgMap, store: &mut E| {
+        let from = arg_map.get("from").unwrap();
+        let to = arg_map.get("to").unwrap();
+        info!("Comparing \"{}\" with \"{}\"", from, to);
+        let diff = RosterDiff::between(&load_roster(from, store)?, &load_roster(to, store)?);
+        for line in diff.describe() {
+            info!("{}", line);
+        }
+        Ok(message("diff_snapshots.found", &[("count", &diff.change_count()), ("from", from), ("to", to)]))
+    };
+
+    CommandHandler::new(
+        text("diff_snapshots.description"),
+        Regex::new(&text("diff_snapshots.pattern")).unwrap(),
+        vec!["from", "to"],
+        executor,
+    ).with_required_role(Role::Viewer)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::get_handler;
+    use crate::command::HandleCommand;
+    use crate::command::handler::CommandHandler;
+    use crate::employee_store::MockEmployeeStore;
+
+    const MATCHING_COMMAND: &str = "Diff before-reorg current";
+    const NON_MATCHING_COMMAND: &'static str = "Diff before reorg and now";
+
+    fn run_test_against_matcher(command_text: &str, expected_return: bool) {
+        let test_handler: CommandHandler<MockEmployeeStore> = get_handler();
+        assert_eq!(test_handler.matches_command_text(command_text), expected_return)
+    }
+
+    #[test]
+    fn test_matcher_handles_matching_pattern() {
+        run_test_against_matcher(MATCHING_COMMAND, true);
+    }
+
+    #[test]
+    fn test_matcher_handles_non_matching_pattern() {
+        run_test_against_matcher(NON_MATCHING_COMMAND, false);
+    }
+
+    #[test]
+    fn test_executor_compares_current_roster_with_itself() {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_retrieve_all_employees()
+            .times(2)
+            .returning(|| vec![]);
+
+        let result = get_handler()
+            .execute_command("Diff current current", &mut mock_store);
+
+        assert_eq!(result, Ok("Found 0 differences between \"current\" and \"current\"".to_string()));
+    }
+
+    #[test]
+    fn test_executor_errors_for_missing_snapshot() {
+        let result = get_handler()
+            .execute_command("Diff no-such-snapshot-label current", &mut MockEmployeeStore::new());
+
+        assert_eq!(result, Err("Snapshot \"no-such-snapshot-label\" does not exist".to_string()));
+    }
+}
@@ -1,10 +1,9 @@
 use super::{ParsedArgMap, CommandHandler, CommandExecutor};
+use crate::access::Role;
 use crate::employee_store::EmployeeStore;
+use crate::locale::{message, text};
 use regex::Regex;
 
-const MATCH_PATTERN_DESCRIPTION: &'static str = "List Departments";
-const REGEX_PATTERN: &'static str = r"^List departments$";
-
 pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
     let executor: CommandExecutor<E> = |_arg_map: ParsedArgMap, store: &mut E| {
         info!("Retrieving department list");
@@ -12,15 +11,15 @@ pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
         //  do we want to allow for that case?
         let dept_list = store.list_departments();
         info!("{}", dept_list.join(", "));
-        Ok(format!("Successfully retrieved full list of {} departments", dept_list.len()))
+        Ok(message("list_departments.listed", &[("count", &dept_list.len())]))
     };
 
     CommandHandler::new(
-        MATCH_PATTERN_DESCRIPTION,
-        Regex::new(REGEX_PATTERN).unwrap(),
+        text("list_departments.description"),
+        Regex::new(&text("list_departments.pattern")).unwrap(),
         vec![],
         executor,
-    )
+    ).with_required_role(Role::Viewer)
 }
 
 
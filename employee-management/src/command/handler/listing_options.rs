@@ -0,0 +1,93 @@
+use regex::Regex;
+
+use crate::employee_store::{ListingOptions, ListingPage, PageRequest, SortKey};
+use crate::locale::{message, text};
+
+// Appended to a handler's regex pattern prefix, to capture an optional "sorted by ... page ... of size ..." suffix
+pub fn with_options_pattern(pattern_prefix: &str) -> Regex {
+    Regex::new(&format!("{}{}$", pattern_prefix, text("listing_options.pattern"))).unwrap()
+}
+
+fn parse_number(text: &str) -> Result<usize, String> {
+    match text.parse::<usize>() {
+        Ok(number) if number > 0 => Ok(number),
+        _ => Err(message("listing_options.invalid_number", &[("text", &text)]))
+    }
+}
+
+pub fn parse_listing_options(options_text: &str) -> Result<ListingOptions, String> {
+    let captures = match Regex::new(&text("listing_options.parsing_pattern")).unwrap().captures(options_text) {
+        Some(captures) => captures,
+        None => return Err(message("listing_options.unparseable", &[("text", &options_text.trim())])),
+    };
+    let sort_key = match captures.name("sort_key").map(|m| m.as_str().to_string()) {
+        Some(key) if key == text("listing_options.sort_key.size") => SortKey::Size,
+        Some(key) if key == text("listing_options.sort_key.date_added") => SortKey::DateAdded,
+        _ => SortKey::Name,
+    };
+    let page = match (captures.name("page_number"), captures.name("page_size")) {
+        (Some(number), Some(size)) => Some(PageRequest {
+            number: parse_number(number.as_str())?,
+            size: parse_number(size.as_str())?,
+        }),
+        _ => None
+    };
+    Ok(ListingOptions { sort_key, descending: captures.name("descending").is_some(), page })
+}
+
+pub fn describe_page<T>(page: &ListingPage<T>, item_description: &str) -> String {
+    message("listing_options.page", &[
+        ("page_number", &page.page_number),
+        ("page_count", &page.page_count),
+        ("total_items", &page.total_items),
+        ("items", &item_description),
+    ])
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{describe_page, parse_listing_options};
+    use crate::employee_store::{ListingOptions, ListingPage, PageRequest, SortKey};
+
+    #[test]
+    fn test_parse_empty_options_gives_defaults() {
+        assert_eq!(parse_listing_options(""), Ok(ListingOptions::new()));
+    }
+
+    #[test]
+    fn test_parse_sort_and_page_options() {
+        assert_eq!(
+            parse_listing_options(" sorted by date added desc page 3 of size 20"),
+            Ok(ListingOptions {
+                sort_key: SortKey::DateAdded, descending: true, page: Some(PageRequest { number: 3, size: 20 }),
+            })
+        );
+        assert_eq!(
+            parse_listing_options(" page 1 of size 5"),
+            Ok(ListingOptions { page: Some(PageRequest { number: 1, size: 5 }), ..ListingOptions::new() })
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_zero_page_size() {
+        assert_eq!(
+            parse_listing_options(" page 1 of size 0"),
+            Err("Page number and size must be whole numbers of at least 1 (got \"0\")".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_options() {
+        assert_eq!(
+            parse_listing_options(" sorted by height"),
+            Err("Could not parse listing options \"sorted by height\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_describe_page() {
+        let page: ListingPage<String> = ListingPage { items: vec![], page_number: 2, page_count: 5, total_items: 23 };
+        assert_eq!(describe_page(&page, "departments"), "Page 2 of 5 (23 departments in total)");
+    }
+}
@@ -1,42 +1,62 @@
 mod add_employee;
+mod create_department;
 mod delete_department;
 mod delete_employee;
+mod diff_snapshots;
 mod list_departments;
+mod listing_options;
+mod report;
+mod restore_snapshot;
 mod retrieve_all_employees;
 mod retrieve_employees_by_department;
+mod set_empty_department_policy;
+mod take_snapshot;
 
 use std::collections::HashMap;
 
 use regex::{Captures, Regex};
 
+use crate::access::Role;
 use crate::employee_store::EmployeeStore;
+use crate::locale::message;
 
 use super::HandleCommand;
 
 
 pub type ParsedArgMap = HashMap<String, String>;
 pub type CommandExecutor<E> = fn(ParsedArgMap, &mut E) -> Result<String, String>;
+// Describes what the command would do, without changing anything - errors should match those the executor would give
+pub type CommandPreviewer<E> = fn(ParsedArgMap, &E) -> Result<String, String>;
 
 pub fn get_all_handlers<E: EmployeeStore>() -> Vec<CommandHandler<E>> {
     vec![
         add_employee::get_handler(),
+        create_department::get_handler(),
         delete_department::get_handler(),
         delete_employee::get_handler(),
+        diff_snapshots::get_handler(),
         list_departments::get_handler(),
+        report::get_handler(),
+        restore_snapshot::get_handler(),
         retrieve_all_employees::get_handler(),
         retrieve_employees_by_department::get_handler(),
+        set_empty_department_policy::get_handler(),
+        take_snapshot::get_handler(),
     ]
 }
 
 pub struct CommandHandler<E: EmployeeStore> {
-    match_pattern_description: &'static str,
+    match_pattern_description: String,
     matcher_regex: Regex,
     expected_args: Vec<String>,
     executor: CommandExecutor<E>,
+    required_role: Role,
+    requires_confirmation: bool,
+    previewer: Option<CommandPreviewer<E>>,
 }
 
 impl<E: EmployeeStore> CommandHandler<E> {
-    pub fn new(match_pattern_description: &'static str,
+    pub fn new(match_pattern_description: String,
                matcher_regex: Regex,
                expected_args: Vec<&str>,
                executor: CommandExecutor<E>,
@@ -47,8 +67,28 @@ impl<E: EmployeeStore> CommandHandler<E> {
             matcher_regex,
             expected_args: expected_args_ownable,
             executor,
+            required_role: Role::Editor,
+            requires_confirmation: false,
+            previewer: None,
         }
     }
+
+    // Handlers need the editor role unless set otherwise - admin is reserved for commands that can't easily be undone
+    pub fn with_required_role(mut self, required_role: Role) -> CommandHandler<E> {
+        self.required_role = required_role;
+        self
+    }
+
+    pub fn with_previewer(mut self, previewer: CommandPreviewer<E>) -> CommandHandler<E> {
+        self.previewer = Some(previewer);
+        self
+    }
+
+    // For commands that destroy data, whatever role they need - the preview is shown when asking
+    pub fn with_confirmation_required(mut self) -> CommandHandler<E> {
+        self.requires_confirmation = true;
+        self
+    }
 }
 
 fn extract_args(regex: &Regex, expected_args: &Vec<String>, command_text: &str) -> Option<ParsedArgMap> {
@@ -75,23 +115,58 @@ fn extract_args(regex: &Regex, expected_args: &Vec<String>, command_text: &str)
 
 impl<E: 'static + EmployeeStore> HandleCommand<E> for CommandHandler<E> {
     fn matches_command_text(&self, command_text: &str) -> bool {
-        let result = self.matcher_regex.is_match(command_text);
+        let result = self.matches_command_text_quietly(command_text);
         let result_description = if result { "successfully matched" } else { "did not match" };
         debug!("Command text {} pattern \"{}\"", result_description, self.match_pattern_description);
         result
     }
 
+    fn matches_command_text_quietly(&self, command_text: &str) -> bool {
+        self.matcher_regex.is_match(command_text)
+    }
+
     fn execute_command(&self, command_text: &str, employee_store: &mut E) -> Result<String, String> {
         match extract_args(&self.matcher_regex, &self.expected_args, command_text) {
             Some(arg_map) =>
                 (self.executor)(arg_map.clone(), employee_store),
             None =>
-                Err(format!("Could not parse args using pattern \"{}\"", self.match_pattern_description))
+                Err(message("handler.unparseable", &[("description", &self.match_pattern_description)]))
         }
     }
 
     fn describe(&self) -> String {
-        self.match_pattern_description.to_string()
+        self.match_pattern_description.clone()
+    }
+
+    fn interpret(&self, command_text: &str) -> String {
+        match extract_args(&self.matcher_regex, &self.expected_args, command_text) {
+            Some(arg_map) if !self.expected_args.is_empty() => {
+                let arg_descriptions: Vec<String> = self.expected_args
+                    .iter()
+                    .map(|arg_key| format!("{} = \"{}\"", arg_key, arg_map[arg_key]))
+                    .collect();
+                message("handler.interpretation", &[
+                    ("description", &self.match_pattern_description), ("arguments", &arg_descriptions.join(", ")),
+                ])
+            }
+            _ => format!("\"{}\"", self.match_pattern_description)
+        }
+    }
+
+    fn required_role(&self) -> Role {
+        self.required_role
+    }
+
+    fn requires_confirmation(&self) -> bool {
+        self.requires_confirmation
+    }
+
+    fn preview_command(&self, command_text: &str, employee_store: &E) -> Result<String, String> {
+        match (extract_args(&self.matcher_regex, &self.expected_args, command_text), self.previewer) {
+            (Some(arg_map), Some(previewer)) => previewer(arg_map, employee_store),
+            (Some(_), None) => Ok(message("handler.would_run", &[("interpretation", &self.interpret(command_text))])),
+            (None, _) => Err(message("handler.unparseable", &[("description", &self.match_pattern_description)]))
+        }
     }
 }
 
@@ -100,6 +175,7 @@ mod tests {
     use log::Level;
     use regex::Regex;
 
+    use crate::access::Role;
     use crate::employee_store::EmployeeStoreImpl;
 
     use super::{CommandExecutor, CommandHandler, ParsedArgMap};
@@ -123,10 +199,13 @@ mod tests {
 
     fn get_test_handler() -> CommandHandler<EmployeeStoreImpl> {
         CommandHandler {
-            match_pattern_description: "Use (argument 1) and (argument 2)",
+            match_pattern_description: "Use (argument 1) and (argument 2)".to_string(),
             matcher_regex: Regex::new(r"^Use (?P<arg_1>.*) and (?P<arg_2>.*)$").unwrap(),
             expected_args: vec!["arg_1".to_string(), "arg_2".to_string()],
             executor: STUB_EXECUTOR,
+            required_role: Role::Editor,
+            requires_confirmation: false,
+            previewer: None,
         }
     }
 
@@ -179,17 +258,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_interpret_describes_parsed_args() {
+        assert_eq!(
+            get_test_handler().interpret(MATCHING_COMMAND),
+            "\"Use (argument 1) and (argument 2)\" with arg_1 = \"value 1\", arg_2 = \"value 2\""
+        );
+    }
+
+    #[test]
+    fn test_interpret_non_matching_command_gives_description_only() {
+        assert_eq!(get_test_handler().interpret(NON_MATCHING_COMMAND), "\"Use (argument 1) and (argument 2)\"");
+    }
+
+    #[test]
+    fn test_preview_without_previewer_describes_interpretation() {
+        assert_eq!(
+            get_test_handler().preview_command(MATCHING_COMMAND, &EmployeeStoreImpl::new()),
+            Ok("Would run \"Use (argument 1) and (argument 2)\" with arg_1 = \"value 1\", arg_2 = \"value 2\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_preview_calls_previewer() {
+        let handler = get_test_handler().with_previewer(|arg_map: ParsedArgMap, _store: &EmployeeStoreImpl| {
+            Ok(format!("Would use {}", arg_map["arg_1"]))
+        });
+        assert_eq!(
+            handler.preview_command(MATCHING_COMMAND, &EmployeeStoreImpl::new()),
+            Ok("Would use value 1".to_string())
+        );
+    }
+
     #[test]
     #[allow(unused_must_use)]
     #[should_panic(expected = "Could not find arg \"arg_2\" in Captures")]
     fn test_execution_panics_in_a_descriptive_way_if_expected_args_dont_match_regex_pattern() {
         let handler = CommandHandler {
-            match_pattern_description: "",
+            match_pattern_description: String::new(),
             matcher_regex: Regex::new(r"^Take value (?P<arg_1>.*)$").unwrap(),
             expected_args: vec!["arg_2".to_string()],
             executor: STUB_EXECUTOR,
+            required_role: Role::Editor,
+            requires_confirmation: false,
+            previewer: None,
         };
         handler.execute_command("Take value foo", &mut EmployeeStoreImpl::new());
     }
 
-}
+    #[test]
+    fn test_only_hard_to_undo_handlers_require_admin_role() {
+        let admin_handlers: Vec<String> = super::get_all_handlers::<EmployeeStoreImpl>()
+            .iter()
+            .filter(|handler| handler.required_role() == Role::Admin)
+            .map(|handler| handler.describe())
+            .collect();
+        assert_eq!(admin_handlers, vec![
+            "Delete department (department name)",
+            "Restore (label)",
+            "Set empty department policy (auto-delete|keep|require-create)",
+        ]);
+    }
+
+    #[test]
+    fn test_handlers_that_destroy_data_require_confirmation() {
+        let confirmed_handlers: Vec<String> = super::get_all_handlers::<EmployeeStoreImpl>()
+            .iter()
+            .filter(|handler| handler.requires_confirmation())
+            .map(|handler| handler.describe())
+            .collect();
+        assert_eq!(confirmed_handlers, vec![
+            "Delete department (department name)",
+            "Delete (employee name) from (department name)",
+            "Restore (label)",
+            "Set empty department policy (auto-delete|keep|require-create)",
+        ]);
+    }
+
+}
\ No newline at end of file
@@ -0,0 +1,115 @@
+use super::{ParsedArgMap, CommandHandler, CommandExecutor};
+use crate::access::Role;
+use crate::employee_store::EmployeeStore;
+use crate::locale::{message, text};
+use crate::report::RosterStatistics;
+use regex::Regex;
+
+pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
+    let executor: CommandExecutor<E> = |arg_map: ParsedArgMap, store: &mut E| {
+        info!("Producing headcount report");
+        let statistics = RosterStatistics::from_departments(&store.retrieve_all_employees());
+        for department in &statistics.empty_departments {
+            warn!("Department \"{}\" has no employees", department);
+        }
+        if arg_map.get("format").unwrap().is_empty() {
+            for line in statistics.render_text() {
+                info!("{}", line);
+            }
+        } else {
+            info!("{}", statistics.render_json());
+        }
+        Ok(message("report.produced", &[("count", &statistics.departments.len())]))
+    };
+
+    CommandHandler::new(
+        text("report.description"),
+        Regex::new(&text("report.pattern")).unwrap(),
+        vec!["format"],
+        executor,
+    ).with_required_role(Role::Viewer)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::get_handler;
+    use crate::command::HandleCommand;
+    use crate::command::handler::CommandHandler;
+    use crate::employee_store::{MockEmployeeStore, DepartmentInfo};
+    use log::Level;
+
+    const MATCHING_COMMAND: &str = "Report";
+    const MATCHING_JSON_COMMAND: &str = "Report as JSON";
+    const NON_MATCHING_COMMAND: &'static str = "Report on the Pie QC department";
+
+    fn run_test_against_matcher(command_text: &str, expected_return: bool) {
+        let test_handler: CommandHandler<MockEmployeeStore> = get_handler();
+        assert_eq!(test_handler.matches_command_text(command_text), expected_return)
+    }
+
+    #[test]
+    fn test_matcher_handles_matching_pattern() {
+        run_test_against_matcher(MATCHING_COMMAND, true);
+        run_test_against_matcher(MATCHING_JSON_COMMAND, true);
+    }
+
+    #[test]
+    fn test_matcher_handles_non_matching_pattern() {
+        run_test_against_matcher(NON_MATCHING_COMMAND, false);
+    }
+
+    fn mock_store() -> MockEmployeeStore {
+        let mock_return_department_infos = vec![
+            DepartmentInfo {
+                department: "Pie Analysis".to_string(),
+                employee_names: vec!["Bob Bobertson".to_string(), "Weebl Bull".to_string()]
+            },
+            DepartmentInfo {
+                department: "Pie Rejection".to_string(),
+                employee_names: vec![]
+            },
+        ];
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_retrieve_all_employees()
+            .times(1)
+            .with()
+            .return_once(move || mock_return_department_infos);
+        mock_store
+    }
+
+    #[test]
+    fn test_executor_logs_text_report_and_warns_about_empty_departments() {
+        testing_logger::setup();
+
+        let result = get_handler()
+            .execute_command(MATCHING_COMMAND, &mut mock_store());
+
+        assert_eq!(result, Ok("Successfully produced report for 2 departments".to_string()));
+
+        testing_logger::validate(|captured_logs| {
+            assert_eq!(captured_logs.len(), 12);
+            assert_eq!(captured_logs[0].body, "Producing headcount report");
+            assert_eq!(captured_logs[1].body, "Department \"Pie Rejection\" has no employees");
+            assert_eq!(captured_logs[1].level, Level::Warn);
+            assert_eq!(captured_logs[2].body, "Department    | Headcount");
+            assert_eq!(captured_logs[7].body, "Largest department(s): Pie Analysis (2)");
+        });
+    }
+
+    #[test]
+    fn test_executor_logs_json_report() {
+        testing_logger::setup();
+
+        let result = get_handler()
+            .execute_command(MATCHING_JSON_COMMAND, &mut mock_store());
+
+        assert_eq!(result, Ok("Successfully produced report for 2 departments".to_string()));
+
+        testing_logger::validate(|captured_logs| {
+            assert_eq!(captured_logs.len(), 3);
+            assert!(captured_logs[2].body.starts_with("{\n  \"departments\": ["));
+        });
+    }
+}
@@ -0,0 +1,168 @@
+use super::{ParsedArgMap, CommandHandler, CommandExecutor, CommandPreviewer};
+use crate::access::Role;
+use crate::employee_store::{DepartmentInfo, EmployeeStore, EmptyDepartmentPolicy};
+use crate::locale::{message, text};
+use crate::snapshot::{load_snapshot, snapshot_directory, RosterDiff};
+use regex::Regex;
+
+// The snapshot may have been taken under a policy that kept empty departments, which the current one might not allow
+fn departments_to_restore<E: EmployeeStore>(store: &E, label: &str) -> Result<Vec<DepartmentInfo>, String> {
+    let mut departments = load_snapshot(&snapshot_directory(), label)?;
+    if store.get_empty_department_policy() == EmptyDepartmentPolicy::AutoDelete {
+        departments.retain(|info| !info.employee_names.is_empty());
+    }
+    Ok(departments)
+}
+
+pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
+    let executor: CommandExecutor<E> = |arg_map: ParsedArgMap, store: &mut E| {
+        let label = arg_map.get("label").unwrap();
+        // Load (which also checks each department is listed once, so every create below succeeds) before clearing
+        //  anything, so a missing, corrupt or inconsistent snapshot leaves the roster as it is
+        let departments = departments_to_restore(store, label)?;
+        info!("Restoring snapshot \"{}\"", label);
+        for department in store.list_departments() {
+            store.delete_department(&department)?;
+        }
+        for info in &departments {
+            store.create_department(&info.department)?;
+            for employee_name in &info.employee_names {
+                store.add_employee(employee_name, &info.department);
+            }
+        }
+        Ok(message("restore_snapshot.restored", &[("label", label), ("count", &departments.len())]))
+    };
+
+    let previewer: CommandPreviewer<E> = |arg_map: ParsedArgMap, store: &E| {
+        let label = arg_map.get("label").unwrap();
+        let departments = departments_to_restore(store, label)?;
+        let diff = RosterDiff::between(&store.retrieve_all_employees(), &departments);
+        let summary = message("restore_snapshot.would_restore", &[("label", label), ("count", &diff.change_count())]);
+        let mut lines = vec![summary];
+        lines.extend(diff.describe());
+        Ok(lines.join("\n"))
+    };
+
+    CommandHandler::new(
+        text("restore_snapshot.description"),
+        Regex::new(&text("restore_snapshot.pattern")).unwrap(),
+        vec!["label"],
+        executor,
+    ).with_required_role(Role::Admin).with_previewer(previewer).with_confirmation_required()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::get_handler;
+    use crate::command::HandleCommand;
+    use crate::command::handler::CommandHandler;
+    use crate::employee_store::{
+        DepartmentInfo, EmployeeStore, EmployeeStoreImpl, EmptyDepartmentPolicy, MockEmployeeStore,
+    };
+    use crate::snapshot::{save_snapshot, set_snapshot_directory};
+    use std::env;
+    use std::fs;
+
+    const MATCHING_COMMAND: &str = "Restore before-reorg";
+    const NON_MATCHING_COMMAND: &'static str = "Put things back how they were";
+
+    fn run_test_against_matcher(command_text: &str, expected_return: bool) {
+        let test_handler: CommandHandler<MockEmployeeStore> = get_handler();
+        assert_eq!(test_handler.matches_command_text(command_text), expected_return)
+    }
+
+    #[test]
+    fn test_matcher_handles_matching_pattern() {
+        run_test_against_matcher(MATCHING_COMMAND, true);
+    }
+
+    #[test]
+    fn test_matcher_handles_non_matching_pattern() {
+        run_test_against_matcher(NON_MATCHING_COMMAND, false);
+    }
+
+    #[test]
+    fn test_executor_leaves_store_untouched_for_missing_snapshot() {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store.expect_list_departments().times(0);
+        mock_store.expect_delete_department().times(0);
+
+        let result = get_handler()
+            .execute_command("Restore no-such-snapshot-label", &mut mock_store);
+
+        assert_eq!(result, Err("Snapshot \"no-such-snapshot-label\" does not exist".to_string()));
+    }
+
+    #[test]
+    fn test_previewer_errors_for_missing_snapshot() {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store.expect_retrieve_all_employees().times(0);
+
+        let result = get_handler()
+            .preview_command("Restore no-such-snapshot-label", &mock_store);
+
+        assert_eq!(result, Err("Snapshot \"no-such-snapshot-label\" does not exist".to_string()));
+    }
+
+    #[test]
+    fn test_executor_leaves_store_untouched_for_snapshot_with_duplicate_departments() {
+        let directory = env::temp_dir().join(format!("employee-management-restore-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let department = |name: &str| DepartmentInfo { department: name.to_string(), employee_names: vec![] };
+        save_snapshot(&directory, "doubled", &[department("Pie Rejection"), department("Pie Rejection")]).unwrap();
+        set_snapshot_directory(&directory);
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store.expect_list_departments().times(0);
+        mock_store.expect_delete_department().times(0);
+
+        let result = get_handler().execute_command("Restore doubled", &mut mock_store);
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            result,
+            Err("Snapshot \"doubled\" lists department \"Pie Rejection\" more than once".to_string())
+        );
+    }
+
+    fn restore_into(store: &mut EmployeeStoreImpl, directory_name: &str) -> Result<String, String> {
+        let directory = env::temp_dir()
+            .join(format!("employee-management-restore-{}-{}", directory_name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let snapshot = vec![
+            DepartmentInfo { department: "Cake Tasting".to_string(), employee_names: vec!["Sally".to_string()] },
+            DepartmentInfo { department: "Pie Rejection".to_string(), employee_names: vec![] },
+        ];
+        save_snapshot(&directory, "before-reorg", &snapshot).unwrap();
+        set_snapshot_directory(&directory);
+        store.add_employee(&"Bob".to_string(), &"Accounts".to_string());
+
+        let result = get_handler().execute_command("Restore before-reorg", store);
+        fs::remove_dir_all(&directory).unwrap();
+        result
+    }
+
+    #[test]
+    fn test_executor_replaces_roster_with_snapshot() {
+        let mut store = EmployeeStoreImpl::with_empty_department_policy(EmptyDepartmentPolicy::Keep);
+
+        let result = restore_into(&mut store, "keep");
+
+        assert_eq!(result, Ok("Successfully restored snapshot \"before-reorg\" (2 departments)".to_string()));
+        assert_eq!(store.list_departments(), vec!["Cake Tasting".to_string(), "Pie Rejection".to_string()]);
+        assert_eq!(
+            store.retrieve_employees_by_department(&"Cake Tasting".to_string()),
+            Some(vec!["Sally".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_executor_skips_empty_departments_under_auto_delete() {
+        let mut store = EmployeeStoreImpl::with_empty_department_policy(EmptyDepartmentPolicy::AutoDelete);
+
+        let result = restore_into(&mut store, "auto-delete");
+
+        assert_eq!(result, Ok("Successfully restored snapshot \"before-reorg\" (1 departments)".to_string()));
+        assert_eq!(store.list_departments(), vec!["Cake Tasting".to_string()]);
+    }
+}
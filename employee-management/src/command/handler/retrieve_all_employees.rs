@@ -1,28 +1,31 @@
 use super::{ParsedArgMap, CommandHandler, CommandExecutor};
+use super::listing_options::{describe_page, parse_listing_options, with_options_pattern};
+use crate::access::Role;
 use crate::employee_store::EmployeeStore;
-use regex::Regex;
-
-const MATCH_PATTERN_DESCRIPTION: &'static str = "Retrieve all departments";
-const REGEX_PATTERN: &'static str = r"^Retrieve all departments$";
+use crate::locale::{message, text};
 
 pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
-    let executor: CommandExecutor<E> = |_arg_map: ParsedArgMap, store: &mut E| {
+    let executor: CommandExecutor<E> = |arg_map: ParsedArgMap, store: &mut E| {
+        let options = parse_listing_options(arg_map.get("options").unwrap())?;
         info!("Retrieving full employee list");
         // TODO - if this were connecting to an actual database it would be able to error -
         //  do we want to allow for that case?
-        let departments = store.retrieve_all_employees();
-        for dept_info in &departments {
+        let page = store.retrieve_all_employees_with_options(&options);
+        for dept_info in &page.items {
             info!("{} - {}", dept_info.department, dept_info.employee_names.join(", "));
         }
-        Ok(format!("Successfully retrieved employees for {} departments", departments.len()))
+        if options.page.is_some() {
+            info!("{}", describe_page(&page, &text("retrieve_all_employees.items")));
+        }
+        Ok(message("retrieve_all_employees.retrieved", &[("count", &page.items.len())]))
     };
 
     CommandHandler::new(
-        MATCH_PATTERN_DESCRIPTION,
-        Regex::new(REGEX_PATTERN).unwrap(),
-        vec![],
+        text("retrieve_all_employees.description"),
+        with_options_pattern(&text("retrieve_all_employees.pattern_prefix")),
+        vec!["options"],
         executor,
-    )
+    ).with_required_role(Role::Viewer)
 }
 
 
@@ -31,8 +34,9 @@ mod tests {
     use super::get_handler;
     use crate::command::HandleCommand;
     use crate::command::handler::CommandHandler;
-    use crate::employee_store::{MockEmployeeStore, DepartmentInfo};
+    use crate::employee_store::{MockEmployeeStore, DepartmentInfo, ListingOptions, ListingPage, PageRequest, SortKey};
     use log::Level;
+    use mockall::predicate::eq;
 
     const MATCHING_COMMAND: &str = "Retrieve all departments";
     const NON_MATCHING_COMMAND: &'static str = "Get me all the departments!";
@@ -47,6 +51,11 @@ mod tests {
         run_test_against_matcher(MATCHING_COMMAND, true);
     }
 
+    #[test]
+    fn test_matcher_handles_matching_pattern_with_options() {
+        run_test_against_matcher("Retrieve all departments sorted by size desc page 2 of size 10", true);
+    }
+
     #[test]
     fn test_matcher_handles_non_matching_pattern() {
         run_test_against_matcher(NON_MATCHING_COMMAND, false);
@@ -68,10 +77,12 @@ mod tests {
         ];
         let mut mock_store = MockEmployeeStore::new();
         mock_store
-            .expect_retrieve_all_employees()
+            .expect_retrieve_all_employees_with_options()
             .times(1)
-            .with()
-            .return_once(move || mock_return_department_infos);
+            .with(eq(ListingOptions::new()))
+            .return_once(move |_options| ListingPage {
+                items: mock_return_department_infos, page_number: 1, page_count: 1, total_items: 2,
+            });
 
         let result = get_handler()
             .execute_command(MATCHING_COMMAND, &mut mock_store);
@@ -88,4 +99,37 @@ mod tests {
             assert_eq!(captured_logs[2].body, "Stealthy Buccaneering - Chris the Ninja Pirate");
         });
     }
+
+    #[test]
+    fn test_executor_passes_options_to_store_and_logs_page_indicator() {
+        testing_logger::setup();
+
+        let expected_options = ListingOptions {
+            sort_key: SortKey::Size, descending: true, page: Some(PageRequest { number: 2, size: 1 }),
+        };
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_retrieve_all_employees_with_options()
+            .times(1)
+            .with(eq(expected_options))
+            .return_once(move |_options| ListingPage {
+                items: vec![DepartmentInfo {
+                    department: "Pie Analysis".to_string(), employee_names: vec!["Weebl Bull".to_string()],
+                }],
+                page_number: 2,
+                page_count: 3,
+                total_items: 3,
+            });
+
+        let result = get_handler()
+            .execute_command("Retrieve all departments sorted by size desc page 2 of size 1", &mut mock_store);
+
+        assert_eq!(result, Ok("Successfully retrieved employees for 1 departments".to_string()));
+
+        testing_logger::validate(|captured_logs| {
+            assert_eq!(captured_logs.len(), 3);
+            assert_eq!(captured_logs[1].body, "Pie Analysis - Weebl Bull");
+            assert_eq!(captured_logs[2].body, "Page 2 of 3 (3 departments in total)");
+        });
+    }
 }
\ No newline at end of file
@@ -1,31 +1,40 @@
 use super::{ParsedArgMap, CommandHandler, CommandExecutor};
-use crate::employee_store::EmployeeStore;
-use regex::Regex;
-
-const MATCH_PATTERN_DESCRIPTION: &'static str = "Retrieve department (department name)";
-const REGEX_PATTERN: &'static str = r"^Retrieve department (?P<department>.*)$";
+use super::listing_options::{describe_page, parse_listing_options, with_options_pattern};
+use crate::access::Role;
+use crate::employee_store::{EmployeeStore, SortKey};
+use crate::locale::{message, text};
 
 pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
     let executor: CommandExecutor<E> = |arg_map: ParsedArgMap, store: &mut E| {
         let department = arg_map.get("department").unwrap();
+        let options = parse_listing_options(arg_map.get("options").unwrap())?;
+        if options.sort_key == SortKey::Size {
+            return Err(text("retrieve_employees_by_department.invalid_sort"));
+        }
         info!("Retrieving employees for department \"{}\"", department);
-        match store.retrieve_employees_by_department(department) {
-            Some(employees) => {
-                info!("{}", employees.join(", "));
-                Ok(format!("Successfully found {} employees in department \"{}\"", employees.len(), department))
+        match store.retrieve_employees_by_department_with_options(department, &options) {
+            Some(page) => {
+                info!("{}", page.items.join(", "));
+                if options.page.is_some() {
+                    info!("{}", describe_page(&page, &text("retrieve_employees_by_department.items")));
+                }
+                Ok(message(
+                    "retrieve_employees_by_department.found",
+                    &[("count", &page.items.len()), ("department", department)]
+                ))
             },
             None => {
-                Err(format!("Department \"{}\" does not exist", department))
+                Err(message("department_does_not_exist", &[("department", department)]))
             }
         }
     };
 
     CommandHandler::new(
-        MATCH_PATTERN_DESCRIPTION,
-        Regex::new(REGEX_PATTERN).unwrap(),
-        vec!["department"],
+        text("retrieve_employees_by_department.description"),
+        with_options_pattern(&text("retrieve_employees_by_department.pattern_prefix")),
+        vec!["department", "options"],
         executor,
-    )
+    ).with_required_role(Role::Viewer)
 }
 
 
@@ -35,7 +44,7 @@ mod tests {
     use crate::command::HandleCommand;
     use crate::command::handler::CommandHandler;
     use mockall::predicate::eq;
-    use crate::employee_store::MockEmployeeStore;
+    use crate::employee_store::{MockEmployeeStore, ListingOptions, ListingPage, SortKey};
     use log::Level;
 
     const MATCHING_COMMAND: &str = "Retrieve department Pie QC";
@@ -57,7 +66,7 @@ mod tests {
     }
 
     fn run_test_call_executor(
-        mock_return_value: Option<Vec<String>>,
+        mock_return_value: Option<ListingPage<String>>,
         expected_handler_result: Result<String, String>,
         additional_log_entry: Option<(&str, Level)>
     ) {
@@ -65,10 +74,10 @@ mod tests {
 
         let mut mock_store = MockEmployeeStore::new();
         mock_store
-            .expect_retrieve_employees_by_department()
+            .expect_retrieve_employees_by_department_with_options()
             .times(1)
-            .with(eq(String::from("Pie QC")))
-            .return_const(mock_return_value);
+            .with(eq(String::from("Pie QC")), eq(ListingOptions::new()))
+            .return_once(move |_dept, _options| mock_return_value);
 
         let handler_result = get_handler()
             .execute_command(MATCHING_COMMAND, &mut mock_store);
@@ -90,7 +99,12 @@ mod tests {
     #[test]
     fn test_executor_calls_store_handles_existing_department() {
         run_test_call_executor(
-            Some(vec!["Bob Bobertson".to_string(), "Weebl Bull".to_string()]),
+            Some(ListingPage {
+                items: vec!["Bob Bobertson".to_string(), "Weebl Bull".to_string()],
+                page_number: 1,
+                page_count: 1,
+                total_items: 2,
+            }),
             Ok("Successfully found 2 employees in department \"Pie QC\"".to_string()),
             Some(("Bob Bobertson, Weebl Bull", Level::Info))
         );
@@ -104,4 +118,38 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn test_matcher_separates_department_name_from_options() {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_retrieve_employees_by_department_with_options()
+            .times(1)
+            .with(
+                eq(String::from("Pie QC")),
+                eq(ListingOptions { sort_key: SortKey::DateAdded, ..ListingOptions::new() }),
+            )
+            .return_once(|_dept, _options| None);
+
+        let handler_result = get_handler()
+            .execute_command("Retrieve department Pie QC sorted by date added", &mut mock_store);
+
+        assert_eq!(handler_result, Err("Department \"Pie QC\" does not exist".to_string()));
+    }
+
+    #[test]
+    fn test_executor_rejects_sorting_employees_by_size() {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_retrieve_employees_by_department_with_options()
+            .times(0);
+
+        let handler_result = get_handler()
+            .execute_command("Retrieve department Pie QC sorted by size", &mut mock_store);
+
+        assert_eq!(
+            handler_result,
+            Err("Employees within a department can only be sorted by name or date added".to_string())
+        );
+    }
 }
\ No newline at end of file
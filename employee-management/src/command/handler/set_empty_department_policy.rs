@@ -0,0 +1,148 @@
+use super::{ParsedArgMap, CommandHandler, CommandExecutor, CommandPreviewer};
+use crate::access::Role;
+use crate::employee_store::{EmployeeStore, EmptyDepartmentPolicy};
+use crate::locale::{message, text};
+use regex::Regex;
+
+fn unknown_policy(policy_name: &str) -> String {
+    message("set_empty_department_policy.unknown", &[
+        ("policy", &policy_name), ("policies", &EmptyDepartmentPolicy::NAMES.join(", ")),
+    ])
+}
+
+pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
+    let executor: CommandExecutor<E> = |arg_map: ParsedArgMap, store: &mut E| {
+        let policy_name = arg_map.get("policy").unwrap();
+        match EmptyDepartmentPolicy::from_name(policy_name) {
+            Some(policy) => {
+                info!("Setting empty department policy to \"{}\"", policy.name());
+                let removed_departments = store.set_empty_department_policy(policy);
+                if !removed_departments.is_empty() {
+                    info!("Removed empty departments: {}", removed_departments.join(", "));
+                }
+                Ok(message(
+                    "set_empty_department_policy.set",
+                    &[("policy", &policy.name()), ("count", &removed_departments.len())]
+                ))
+            }
+            None => {
+                Err(unknown_policy(policy_name))
+            }
+        }
+    };
+
+    // Only auto-delete removes anything, taking any departments that are already empty with it
+    let previewer: CommandPreviewer<E> = |arg_map: ParsedArgMap, store: &E| {
+        let policy_name = arg_map.get("policy").unwrap();
+        match EmptyDepartmentPolicy::from_name(policy_name) {
+            Some(EmptyDepartmentPolicy::AutoDelete) => {
+                let empty_departments: Vec<String> = store
+                    .retrieve_all_employees()
+                    .into_iter()
+                    .filter(|info| info.employee_names.is_empty())
+                    .map(|info| info.department)
+                    .collect();
+                Ok(message("set_empty_department_policy.would_set_removing", &[
+                    ("policy", &EmptyDepartmentPolicy::AutoDelete.name()),
+                    ("count", &empty_departments.len()),
+                    ("departments", &empty_departments.join(", ")),
+                ]))
+            }
+            Some(policy) => Ok(message("set_empty_department_policy.would_set", &[("policy", &policy.name())])),
+            None => Err(unknown_policy(policy_name))
+        }
+    };
+
+    CommandHandler::new(
+        text("set_empty_department_policy.description"),
+        Regex::new(&text("set_empty_department_policy.pattern")).unwrap(),
+        vec!["policy"],
+        executor,
+    ).with_required_role(Role::Admin).with_previewer(previewer).with_confirmation_required()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::get_handler;
+    use crate::command::HandleCommand;
+    use crate::command::handler::CommandHandler;
+    use mockall::predicate::eq;
+    use crate::employee_store::{MockEmployeeStore, EmptyDepartmentPolicy, DepartmentInfo};
+
+    const MATCHING_COMMAND: &str = "Set empty department policy auto-delete";
+    const NON_MATCHING_COMMAND: &'static str = "Delete empty departments automatically";
+
+    fn run_test_against_matcher(command_text: &str, expected_return: bool) {
+        let test_handler: CommandHandler<MockEmployeeStore> = get_handler();
+        assert_eq!(test_handler.matches_command_text(command_text), expected_return)
+    }
+
+    #[test]
+    fn test_matcher_handles_matching_pattern() {
+        run_test_against_matcher(MATCHING_COMMAND, true);
+    }
+
+    #[test]
+    fn test_matcher_handles_non_matching_pattern() {
+        run_test_against_matcher(NON_MATCHING_COMMAND, false);
+    }
+
+    #[test]
+    fn test_executor_sets_policy_on_store() {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_set_empty_department_policy()
+            .times(1)
+            .with(eq(EmptyDepartmentPolicy::AutoDelete))
+            .return_const(vec!["Pie Rejection".to_string()]);
+
+        let result = get_handler()
+            .execute_command(MATCHING_COMMAND, &mut mock_store);
+
+        assert_eq!(
+            result,
+            Ok("Successfully set empty department policy to \"auto-delete\" (1 empty departments removed)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_executor_rejects_unknown_policy() {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_set_empty_department_policy()
+            .times(0);
+
+        let result = get_handler()
+            .execute_command("Set empty department policy sometimes", &mut mock_store);
+
+        assert_eq!(
+            result,
+            Err("Unknown empty department policy \"sometimes\" - expected one of auto-delete, keep, require-create".to_string())
+        );
+    }
+
+    #[test]
+    fn test_previewer_lists_departments_that_would_be_removed() {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_retrieve_all_employees()
+            .times(1)
+            .returning(|| vec![
+                DepartmentInfo { department: "Pie Analysis".to_string(), employee_names: vec!["Bob".to_string()] },
+                DepartmentInfo { department: "Pie Rejection".to_string(), employee_names: vec![] },
+            ]);
+        mock_store
+            .expect_set_empty_department_policy()
+            .times(0);
+
+        let result = get_handler()
+            .preview_command(MATCHING_COMMAND, &mock_store);
+
+        assert_eq!(
+            result,
+            Ok("Would set empty department policy to \"auto-delete\" and remove 1 empty departments (Pie Rejection)"
+                .to_string())
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use super::{ParsedArgMap, CommandHandler, CommandExecutor, CommandPreviewer};
+use crate::employee_store::EmployeeStore;
+use crate::locale::{message, text};
+use crate::snapshot::{check_snapshot_can_be_saved, save_snapshot, snapshot_directory};
+use regex::Regex;
+
+pub fn get_handler<E: EmployeeStore>() -> CommandHandler<E> {
+    let executor: CommandExecutor<E> = |arg_map: ParsedArgMap, store: &mut E| {
+        let label = arg_map.get("label").unwrap();
+        info!("Taking snapshot \"{}\"", label);
+        let departments = store.retrieve_all_employees();
+        save_snapshot(&snapshot_directory(), label, &departments).map(
+            |path| message(
+                "take_snapshot.saved", &[("label", label), ("count", &departments.len()), ("path", &path.display())]
+            )
+        )
+    };
+
+    let previewer: CommandPreviewer<E> = |arg_map: ParsedArgMap, store: &E| {
+        let label = arg_map.get("label").unwrap();
+        let path = check_snapshot_can_be_saved(&snapshot_directory(), label)?;
+        let count = store.retrieve_all_employees().len();
+        Ok(message("take_snapshot.would_save", &[("label", label), ("count", &count), ("path", &path.display())]))
+    };
+
+    CommandHandler::new(
+        text("take_snapshot.description"),
+        Regex::new(&text("take_snapshot.pattern")).unwrap(),
+        vec!["label"],
+        executor,
+    ).with_previewer(previewer)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::get_handler;
+    use crate::command::HandleCommand;
+    use crate::command::handler::CommandHandler;
+    use crate::employee_store::{DepartmentInfo, MockEmployeeStore};
+    use crate::snapshot::{save_snapshot, set_snapshot_directory};
+    use std::env;
+    use std::fs;
+
+    const MATCHING_COMMAND: &str = "Snapshot before-reorg";
+    const NON_MATCHING_COMMAND: &'static str = "Take a snapshot";
+
+    fn run_test_against_matcher(command_text: &str, expected_return: bool) {
+        let test_handler: CommandHandler<MockEmployeeStore> = get_handler();
+        assert_eq!(test_handler.matches_command_text(command_text), expected_return)
+    }
+
+    #[test]
+    fn test_matcher_handles_matching_pattern() {
+        run_test_against_matcher(MATCHING_COMMAND, true);
+    }
+
+    #[test]
+    fn test_matcher_handles_non_matching_pattern() {
+        run_test_against_matcher(NON_MATCHING_COMMAND, false);
+    }
+
+    #[test]
+    fn test_executor_rejects_invalid_label_without_saving() {
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_retrieve_all_employees()
+            .times(1)
+            .returning(|| vec![]);
+
+        let result = get_handler()
+            .execute_command("Snapshot current", &mut mock_store);
+
+        assert_eq!(result, Err("Snapshot label \"current\" is reserved for the current roster".to_string()));
+    }
+
+    #[test]
+    fn test_previews_snapshot_and_gives_executor_errors() {
+        let directory = env::temp_dir().join(format!("employee-management-preview-snapshot-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        set_snapshot_directory(&directory);
+        save_snapshot(&directory, "existing", &[]).unwrap();
+        let mut mock_store = MockEmployeeStore::new();
+        mock_store
+            .expect_retrieve_all_employees()
+            .returning(|| vec![DepartmentInfo { department: "Pie QC".to_string(), employee_names: vec![] }]);
+
+        let preview = get_handler().preview_command("Snapshot new", &mock_store);
+        let existing = get_handler().preview_command("Snapshot existing", &mock_store);
+        let wrote_new = directory.join("new.json").exists();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            preview,
+            Ok(format!("Would save snapshot \"new\" of 1 departments to \"{}\"", directory.join("new.json").display()))
+        );
+        assert_eq!(existing, Err("Snapshot \"existing\" already exists".to_string()));
+        assert!(!wrote_new);
+    }
+}
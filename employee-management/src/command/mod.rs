@@ -2,24 +2,65 @@ use std::result::Result;
 
 use mockall_derive::automock;
 
+pub use alias::DEFAULT_ALIAS_FILE;
 pub use dispatcher::CommandDispatcher;
+use alias::AliasRegistry;
 use handler::CommandHandler;
-use crate::employee_store::{EmployeeStore, EmployeeStoreImpl};
+use crate::access::{AccessControl, Role};
+use crate::employee_store::{EmployeeStore, EmployeeStoreImpl, JsonFileEmployeeStore, ObservableEmployeeStore};
+use crate::hooks::ShellCommandSubscriber;
+use crate::locale::set_language;
+use crate::snapshot::set_snapshot_directory;
+use crate::settings::{Settings, StoreBackend};
 
+mod alias;
+mod confirmation;
 mod handler;
 mod dispatcher;
 
 #[automock]
 pub trait HandleCommand<E: 'static + EmployeeStore> {
     fn matches_command_text(&self, command_text: &str) -> bool;
+    // As matches_command_text, but without logging - for checking many made-up commands at once
+    fn matches_command_text_quietly(&self, command_text: &str) -> bool;
     fn execute_command(&self, command_text: &str, employee_store: &mut E) -> Result<String, String>;
     fn describe(&self) -> String;
+    fn interpret(&self, command_text: &str) -> String;
+    fn required_role(&self) -> Role;
+    // Whether the user is asked to confirm the command (having seen its preview) before it is run
+    fn requires_confirmation(&self) -> bool;
+    fn preview_command(&self, command_text: &str, employee_store: &E) -> Result<String, String>;
 }
 
-pub type ConcreteDispatcher = CommandDispatcher<EmployeeStoreImpl, CommandHandler<EmployeeStoreImpl>>;
+pub type ConcreteStore = ObservableEmployeeStore<Box<dyn EmployeeStore>>;
+pub type ConcreteDispatcher = CommandDispatcher<ConcreteStore, CommandHandler<ConcreteStore>>;
 
-pub fn get_command_dispatcher() -> ConcreteDispatcher {
+fn open_store(settings: &Settings) -> Result<Box<dyn EmployeeStore>, String> {
+    match (settings.store_backend, &settings.store_path) {
+        (StoreBackend::JsonFile, Some(file_path)) => Ok(Box::new(JsonFileEmployeeStore::open(file_path.clone())?)),
+        (StoreBackend::JsonFile, None) => Err("The json store backend requires a store path".to_string()),
+        (StoreBackend::Memory, _) => Ok(Box::new(EmployeeStoreImpl::new())),
+    }
+}
+
+pub fn get_command_dispatcher(settings: &Settings) -> Result<ConcreteDispatcher, String> {
+    // Handler patterns come from the message catalog, so the language must be set before they are built
+    set_language(&settings.language)?;
+    set_snapshot_directory(&settings.snapshot_directory);
     let command_handlers = handler::get_all_handlers();
-    let employee_store = EmployeeStoreImpl::new();
-    dispatcher::create_dispatcher(command_handlers, employee_store)
+    let mut employee_store = ObservableEmployeeStore::new(open_store(settings)?);
+    let hooks = ShellCommandSubscriber::load(&settings.hooks_file)?;
+    if !hooks.is_empty() {
+        employee_store.subscribe(hooks.into_callback());
+    }
+    let reserved_words = dispatcher::reserved_words(&command_handlers);
+    let aliases = AliasRegistry::load(settings.aliases_file.clone(), &reserved_words)?;
+    let access = if settings.disable_access_control {
+        AccessControl::default()
+    } else {
+        AccessControl::load(&settings.users_file)?
+    };
+    let mut dispatcher = dispatcher::create_dispatcher(command_handlers, employee_store, aliases, access);
+    dispatcher.set_dry_run(settings.dry_run);
+    Ok(dispatcher)
 }
@@ -0,0 +1,92 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+pub type KeyValuePairs = Vec<(String, String)>;
+
+// Simple line-based "key = value" format used for the small local config files (aliases etc.) -
+//  blank lines and lines starting with '#' are ignored, and only the first " = " on a line is significant.
+pub fn parse_key_value_text(text: &str) -> Result<KeyValuePairs, String> {
+    let mut pairs = KeyValuePairs::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.find(" = ") {
+            Some(position) => {
+                pairs.push((line[..position].trim().to_string(), line[position + 3..].trim().to_string()));
+            }
+            None => {
+                return Err(format!("Could not parse line {} (expected \"key = value\"): \"{}\"", index + 1, line));
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+pub fn format_key_value_text(pairs: &KeyValuePairs) -> String {
+    pairs
+        .iter()
+        .map(|(key, value)| format!("{} = {}\n", key, value))
+        .collect()
+}
+
+// A missing file is treated as empty, so that config files only need to exist once something has been set.
+pub fn read_key_value_file(path: &Path) -> Result<KeyValuePairs, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse_key_value_text(&text)
+            .map_err(|msg| format!("Error in config file \"{}\" - {}", path.display(), msg)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(KeyValuePairs::new()),
+        Err(e) => Err(format!("Could not read config file \"{}\" - {}", path.display(), e)),
+    }
+}
+
+pub fn write_key_value_file(path: &Path, pairs: &KeyValuePairs) -> Result<(), String> {
+    fs::write(path, format_key_value_text(pairs))
+        .map_err(|e| format!("Could not write config file \"{}\" - {}", path.display(), e))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{format_key_value_text, parse_key_value_text, read_key_value_file};
+    use std::path::Path;
+
+    fn pair(key: &str, value: &str) -> (String, String) {
+        (key.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn test_parse_ignores_blank_lines_and_comments() {
+        let text = "# A comment\n\nhire = Add {1} to {2}\n  fire = Delete {1} from {2}  \n";
+        assert_eq!(
+            parse_key_value_text(text),
+            Ok(vec![pair("hire", "Add {1} to {2}"), pair("fire", "Delete {1} from {2}")])
+        );
+    }
+
+    #[test]
+    fn test_parse_only_splits_on_first_separator() {
+        assert_eq!(parse_key_value_text("a = b = c"), Ok(vec![pair("a", "b = c")]));
+    }
+
+    #[test]
+    fn test_parse_errors_on_malformed_line() {
+        assert_eq!(
+            parse_key_value_text("good = line\nbad line"),
+            Err("Could not parse line 2 (expected \"key = value\"): \"bad line\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_round_trips_through_parse() {
+        let pairs = vec![pair("hire", "Add {1} to {2}"), pair("onboard", "Add {1} to {2}; Add {1} to Everyone")];
+        assert_eq!(parse_key_value_text(&format_key_value_text(&pairs)), Ok(pairs));
+    }
+
+    #[test]
+    fn test_missing_file_reads_as_empty() {
+        assert_eq!(read_key_value_file(Path::new("/no/such/directory/aliases.conf")), Ok(vec![]));
+    }
+}
@@ -0,0 +1,181 @@
+// A conformance suite that any EmployeeStore implementation can be run against - random sequences of changes are
+//  applied to both the store and a simple reference model, checking after every step that the two agree and that the
+//  store's invariants hold (names sorted with no duplicates, departments sorted, consistent deletion results).
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestCaseError, TestRunner};
+
+use super::{
+    DepartmentInfo, EmployeeDeletionResult, EmployeeStore, EmptyDepartmentPolicy, ListingOptions, ListingPage,
+    PageRequest,
+};
+
+// Small pools of names, so that random operations often refer to employees and departments that already exist
+const EMPLOYEE_NAMES: [&'static str; 4] = ["Bob Bobertson", "Chris the Ninja Pirate", "Hairy Lee", "Weebl Bull"];
+const DEPARTMENTS: [&'static str; 3] = ["Pie Quality Control", "Pie Rejection", "Stealthy Buccaneering"];
+const POLICIES: [EmptyDepartmentPolicy; 3] = [
+    EmptyDepartmentPolicy::AutoDelete, EmptyDepartmentPolicy::Keep, EmptyDepartmentPolicy::RequireExplicitCreate,
+];
+const CASES: u32 = 64;
+const MAX_OPERATIONS: usize = 40;
+
+#[derive(Debug, Clone)]
+enum Operation {
+    AddEmployee(String, String),
+    CreateDepartment(String),
+    DeleteDepartment(String),
+    DeleteEmployee(String, String),
+    SetEmptyDepartmentPolicy(EmptyDepartmentPolicy),
+}
+
+fn employee_name() -> impl Strategy<Value = String> {
+    prop::sample::select(EMPLOYEE_NAMES.to_vec()).prop_map(|name| name.to_string())
+}
+
+fn department() -> impl Strategy<Value = String> {
+    prop::sample::select(DEPARTMENTS.to_vec()).prop_map(|name| name.to_string())
+}
+
+fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+        4 => (employee_name(), department()).prop_map(|(name, dept)| Operation::AddEmployee(name, dept)),
+        1 => department().prop_map(Operation::CreateDepartment),
+        1 => department().prop_map(Operation::DeleteDepartment),
+        3 => (employee_name(), department()).prop_map(|(name, dept)| Operation::DeleteEmployee(name, dept)),
+        1 => prop::sample::select(POLICIES.to_vec()).prop_map(Operation::SetEmptyDepartmentPolicy),
+    ]
+}
+
+struct ReferenceModel {
+    departments: BTreeMap<String, BTreeSet<String>>,
+    policy: EmptyDepartmentPolicy,
+}
+
+impl ReferenceModel {
+    fn department_infos(&self) -> Vec<DepartmentInfo> {
+        self.departments
+            .iter()
+            .map(|(department, names)| DepartmentInfo {
+                department: department.clone(),
+                employee_names: names.iter().cloned().collect(),
+            })
+            .collect()
+    }
+
+    fn remove_empty_departments(&mut self) -> Vec<String> {
+        let empty: Vec<String> = self.departments
+            .iter()
+            .filter(|(_, names)| names.is_empty())
+            .map(|(department, _)| department.clone())
+            .collect();
+        for department in &empty {
+            self.departments.remove(department);
+        }
+        empty
+    }
+}
+
+fn apply<S: EmployeeStore>(operation: &Operation, store: &mut S, model: &mut ReferenceModel)
+    -> Result<(), TestCaseError>
+{
+    match operation {
+        Operation::AddEmployee(name, department) => {
+            store.add_employee(name, department);
+            model.departments.entry(department.clone()).or_default().insert(name.clone());
+        }
+        Operation::CreateDepartment(department) => {
+            let expected_ok = !model.departments.contains_key(department);
+            prop_assert_eq!(store.create_department(department).is_ok(), expected_ok);
+            model.departments.entry(department.clone()).or_default();
+        }
+        Operation::DeleteDepartment(department) => {
+            let result = store.delete_department(department);
+            match model.departments.remove(department) {
+                Some(names) => prop_assert_eq!(result, Ok(DepartmentInfo {
+                    department: department.clone(), employee_names: names.into_iter().collect(),
+                })),
+                None => prop_assert!(result.is_err()),
+            }
+        }
+        Operation::DeleteEmployee(name, department) => {
+            let expected = match model.departments.get_mut(department) {
+                None => EmployeeDeletionResult::NoSuchDepartment,
+                Some(names) if !names.contains(name) => EmployeeDeletionResult::EmployeeNotInDepartment,
+                Some(names) => {
+                    names.remove(name);
+                    if names.is_empty() && model.policy == EmptyDepartmentPolicy::AutoDelete {
+                        model.departments.remove(department);
+                    }
+                    EmployeeDeletionResult::SuccessfullyDeleted
+                }
+            };
+            prop_assert_eq!(store.delete_employee(name, department), expected);
+        }
+        Operation::SetEmptyDepartmentPolicy(policy) => {
+            let mut removed = store.set_empty_department_policy(*policy);
+            removed.sort();
+            model.policy = *policy;
+            let expected_removed = if *policy == EmptyDepartmentPolicy::AutoDelete {
+                model.remove_empty_departments()
+            } else {
+                vec![]
+            };
+            prop_assert_eq!(removed, expected_removed);
+        }
+    }
+    Ok(())
+}
+
+fn check_invariants<S: EmployeeStore>(store: &S, model: &ReferenceModel) -> Result<(), TestCaseError> {
+    let infos = store.retrieve_all_employees();
+    for info in &infos {
+        prop_assert!(
+            info.employee_names.windows(2).all(|pair| pair[0] < pair[1]),
+            "Names in \"{}\" are not sorted and unique: {:?}", info.department, info.employee_names
+        );
+    }
+    prop_assert_eq!(&infos, &model.department_infos());
+    prop_assert_eq!(store.list_departments(), model.departments.keys().cloned().collect::<Vec<String>>());
+    for department in DEPARTMENTS.iter().map(|d| d.to_string()) {
+        prop_assert_eq!(
+            store.retrieve_employees_by_department(&department),
+            model.departments.get(&department).map(|names| names.iter().cloned().collect())
+        );
+    }
+    // Worked out directly rather than with paginate, which the stores use, so a bug there could not hide itself
+    let page = Some(PageRequest { number: 2, size: 1 });
+    let department_infos = model.department_infos();
+    let total_items = department_infos.len();
+    prop_assert_eq!(
+        store.retrieve_all_employees_with_options(&ListingOptions { page, ..ListingOptions::new() }),
+        ListingPage {
+            items: department_infos.into_iter().skip(1).take(1).collect(),
+            page_number: 2,
+            page_count: std::cmp::max(1, total_items),
+            total_items,
+        }
+    );
+    prop_assert_eq!(store.get_empty_department_policy(), model.policy);
+    Ok(())
+}
+
+// Each case starts from a fresh (empty) store, so the factory must not share state between the stores it makes
+pub fn check_conformance<S: EmployeeStore>(new_store: impl Fn() -> S) {
+    let mut runner = TestRunner::new(Config::with_cases(CASES));
+    let result = runner.run(&vec(operation(), 0..MAX_OPERATIONS), |operations| {
+        let mut store = new_store();
+        let mut model = ReferenceModel { departments: BTreeMap::new(), policy: store.get_empty_department_policy() };
+        check_invariants(&store, &model)?;
+        for operation in &operations {
+            apply(operation, &mut store, &mut model)?;
+            check_invariants(&store, &model)?;
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        panic!("Store does not conform: {}", e);
+    }
+}
@@ -1,24 +1,74 @@
 use std::collections::HashMap;
 
-use super::{DepartmentInfo, EmployeeDeletionResult, EmployeeStore};
+use super::{
+    paginate, DepartmentInfo, EmployeeDeletionResult, EmployeeStore, EmptyDepartmentPolicy, ListingOptions,
+    ListingPage, SortKey,
+};
 use super::EmployeeDeletionResult::{EmployeeNotInDepartment, NoSuchDepartment, SuccessfullyDeleted};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct EmployeeStoreImpl {
-    map: HashMap<String, Vec<String>>
+    map: HashMap<String, Vec<String>>,
+    empty_department_policy: EmptyDepartmentPolicy,
+    // A logical clock recording the order in which departments and employees were added (for sorting by date added) -
+    //  entries missing from the sequence maps sort as if added first.
+    sequence_counter: u64,
+    department_sequences: HashMap<String, u64>,
+    employee_sequences: HashMap<(String, String), u64>,
 }
 
 impl EmployeeStoreImpl {
     pub fn new() -> EmployeeStoreImpl {
-        EmployeeStoreImpl { map: HashMap::new() }
+        EmployeeStoreImpl::with_empty_department_policy(EmptyDepartmentPolicy::Keep)
+    }
+
+    pub fn with_empty_department_policy(empty_department_policy: EmptyDepartmentPolicy) -> EmployeeStoreImpl {
+        EmployeeStoreImpl {
+            map: HashMap::new(),
+            empty_department_policy,
+            sequence_counter: 0,
+            department_sequences: HashMap::new(),
+            employee_sequences: HashMap::new(),
+        }
+    }
+
+    fn next_sequence(&mut self) -> u64 {
+        self.sequence_counter += 1;
+        self.sequence_counter
+    }
+
+    fn insert_department(&mut self, department: &str) {
+        let sequence = self.next_sequence();
+        self.map.insert(department.to_string(), vec![]);
+        self.department_sequences.insert(department.to_string(), sequence);
+    }
+
+    fn remove_department(&mut self, department: &String) -> Option<Vec<String>> {
+        self.department_sequences.remove(department);
+        self.employee_sequences.retain(|(d, _), _| d != department);
+        self.map.remove(department)
+    }
+
+    fn department_sequence(&self, department: &String) -> u64 {
+        *self.department_sequences.get(department).unwrap_or(&0)
+    }
+
+    fn employee_sequence(&self, employee_name: &str, department: &str) -> u64 {
+        *self.employee_sequences.get(&(department.to_string(), employee_name.to_string())).unwrap_or(&0)
     }
 }
 
 impl EmployeeStore for EmployeeStoreImpl {
     fn add_employee(&mut self, employee_name: &String, department: &String) {
-        let department_employees = self.map
-            .entry(department.clone())
-            .or_insert(vec![]);
+        if !self.map.contains_key(department) {
+            self.insert_department(department);
+        }
+        if self.map[department].contains(employee_name) {
+            return;
+        }
+        let sequence = self.next_sequence();
+        self.employee_sequences.insert((department.clone(), employee_name.clone()), sequence);
+        let department_employees = self.map.get_mut(department).unwrap();
         department_employees.push(employee_name.clone());
         department_employees.sort_unstable();
     }
@@ -40,6 +90,34 @@ impl EmployeeStore for EmployeeStoreImpl {
         infos
     }
 
+    fn retrieve_all_employees_with_options(&self, options: &ListingOptions) -> ListingPage<DepartmentInfo> {
+        let mut infos = self.retrieve_all_employees();
+        // Stable sorts on top of the name ordering, so ties on size or sequence fall back to name order
+        match options.sort_key {
+            SortKey::Name => {}
+            SortKey::Size => infos.sort_by_key(|info| info.employee_names.len()),
+            SortKey::DateAdded => infos.sort_by_key(|info| self.department_sequence(&info.department)),
+        }
+        if options.descending {
+            infos.reverse();
+        }
+        paginate(infos, options.page)
+    }
+
+    fn retrieve_employees_by_department_with_options(&self, department: &String, options: &ListingOptions)
+        -> Option<ListingPage<String>>
+    {
+        self.retrieve_employees_by_department(department).map(|mut names| {
+            if options.sort_key == SortKey::DateAdded {
+                names.sort_by_key(|name| self.employee_sequence(name, department));
+            }
+            if options.descending {
+                names.reverse();
+            }
+            paginate(names, options.page)
+        })
+    }
+
     fn list_departments(&self) -> Vec<String> {
         let mut departments: Vec<String> = self.map
             .keys()
@@ -59,7 +137,7 @@ impl EmployeeStore for EmployeeStoreImpl {
                     department: department.clone(),
                     employee_names: employee_names.clone(),
                 };
-                self.map.remove(department);
+                self.remove_department(department);
                 Ok(deleted_department)
             }
         }
@@ -74,12 +152,51 @@ impl EmployeeStore for EmployeeStoreImpl {
                     None => EmployeeNotInDepartment,
                     Some(index) => {
                         names_list.remove(index);
+                        let now_empty = names_list.is_empty();
+                        self.employee_sequences.remove(&(department.clone(), employee_name.clone()));
+                        if now_empty && self.empty_department_policy == EmptyDepartmentPolicy::AutoDelete {
+                            self.remove_department(department);
+                        }
                         SuccessfullyDeleted
                     }
                 }
             }
         }
     }
+
+    fn create_department(&mut self, department: &String) -> Result<(), String> {
+        if self.map.contains_key(department) {
+            return Err(format!("Could not create department \"{}\" - department already exists", department));
+        }
+        self.insert_department(department);
+        Ok(())
+    }
+
+    fn get_empty_department_policy(&self) -> EmptyDepartmentPolicy {
+        self.empty_department_policy
+    }
+
+    fn set_empty_department_policy(&mut self, policy: EmptyDepartmentPolicy) -> Vec<String> {
+        self.empty_department_policy = policy;
+        if policy != EmptyDepartmentPolicy::AutoDelete {
+            return vec![];
+        }
+        let mut removed: Vec<String> = self.map
+            .iter()
+            .filter(|(_, names)| names.is_empty())
+            .map(|(department, _)| department.clone())
+            .collect();
+        removed.sort_unstable();
+        for department in &removed {
+            self.remove_department(department);
+        }
+        removed
+    }
+
+    // Everything is held in memory, so there is nothing more to store
+    fn flush(&mut self) -> Result<(), String> {
+        Ok(())
+    }
 }
 
 
@@ -91,7 +208,8 @@ mod tests {
         EmployeeNotInDepartment, NoSuchDepartment, SuccessfullyDeleted,
     };
 
-    use super::{DepartmentInfo, EmployeeStore, EmployeeStoreImpl};
+    use super::{DepartmentInfo, EmployeeStore, EmployeeStoreImpl, EmptyDepartmentPolicy};
+    use crate::employee_store::{ListingOptions, ListingPage, PageRequest, SortKey};
 
     fn department_one() -> String { String::from("Pie Quality Control") }
 
@@ -115,7 +233,7 @@ mod tests {
     }
 
     fn populated_store() -> EmployeeStoreImpl {
-        EmployeeStoreImpl { map: initial_populated_map() }
+        EmployeeStoreImpl { map: initial_populated_map(), ..EmployeeStoreImpl::new() }
     }
 
     fn non_existent_employee() -> String { String::from("Hairy Lee") }
@@ -151,6 +269,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_add_employee_already_in_department_leaves_it_unchanged() {
+        let mut store = EmployeeStoreImpl::new();
+        store.add_employee(&name_one(), &department_one());
+        store.add_employee(&name_one(), &department_one());
+        assert_eq!(store.map.get(&department_one()), Some(&vec![name_one()]));
+    }
+
     #[test]
     fn test_retrieve_employees_for_missing_department_returns_none() {
         assert_eq!(
@@ -257,4 +383,137 @@ mod tests {
         assert_eq!(result, NoSuchDepartment);
         assert_unchanged(store);
     }
+
+    #[test]
+    fn test_delete_last_employee_keeps_empty_department_by_default() {
+        let mut store = populated_store();
+        store.delete_employee(&name_three(), &department_two());
+        assert_eq!(store.retrieve_employees_by_department(&department_two()), Some(vec![]));
+        assert_eq!(store.list_departments(), vec![department_one(), department_two()]);
+    }
+
+    #[test]
+    fn test_delete_last_employee_removes_department_under_auto_delete_policy() {
+        let mut store = populated_store();
+        store.set_empty_department_policy(EmptyDepartmentPolicy::AutoDelete);
+        store.delete_employee(&name_three(), &department_two());
+        assert_eq!(store.retrieve_employees_by_department(&department_two()), None);
+        assert_eq!(store.list_departments(), vec![department_one()]);
+    }
+
+    #[test]
+    fn test_switching_to_auto_delete_policy_removes_existing_empty_departments() {
+        let mut store = populated_store();
+        store.create_department(&non_existent_department()).unwrap();
+        assert_eq!(store.set_empty_department_policy(EmptyDepartmentPolicy::Keep), Vec::<String>::new());
+        assert_eq!(
+            store.set_empty_department_policy(EmptyDepartmentPolicy::AutoDelete),
+            vec![non_existent_department()]
+        );
+        assert_unchanged(store);
+    }
+
+    #[test]
+    fn test_create_department() {
+        let mut store = EmployeeStoreImpl::new();
+        assert_eq!(store.create_department(&department_one()), Ok(()));
+        assert_eq!(store.retrieve_employees_by_department(&department_one()), Some(vec![]));
+    }
+
+    #[test]
+    fn test_create_existing_department_fails() {
+        let mut store = populated_store();
+        assert_eq!(
+            store.create_department(&department_one()),
+            Err("Could not create department \"Pie Quality Control\" - department already exists".to_string())
+        );
+        assert_unchanged(store);
+    }
+
+    fn store_added_in_order(additions: Vec<(&str, &str)>) -> EmployeeStoreImpl {
+        let mut store = EmployeeStoreImpl::new();
+        for (name, department) in additions {
+            store.add_employee(&name.to_string(), &department.to_string());
+        }
+        store
+    }
+
+    fn department_names(page: &ListingPage<DepartmentInfo>) -> Vec<&str> {
+        page.items.iter().map(|info| info.department.as_str()).collect()
+    }
+
+    #[test]
+    fn test_retrieve_all_employees_with_default_options_matches_unpaged_retrieval() {
+        let store = populated_store();
+        assert_eq!(
+            store.retrieve_all_employees_with_options(&ListingOptions::new()),
+            ListingPage { items: store.retrieve_all_employees(), page_number: 1, page_count: 1, total_items: 2 }
+        );
+    }
+
+    #[test]
+    fn test_retrieve_all_employees_sorted_by_size_and_date_added() {
+        let store = store_added_in_order(vec![
+            ("Bob", "Zymurgy"), ("Weebl", "Accounts"), ("Chris", "Accounts"), ("Hairy Lee", "Marketing"),
+        ]);
+        let by_size = ListingOptions { sort_key: SortKey::Size, descending: true, page: None };
+        let by_date = ListingOptions { sort_key: SortKey::DateAdded, descending: false, page: None };
+        assert_eq!(
+            department_names(&store.retrieve_all_employees_with_options(&by_size)),
+            vec!["Accounts", "Zymurgy", "Marketing"]
+        );
+        assert_eq!(
+            department_names(&store.retrieve_all_employees_with_options(&by_date)),
+            vec!["Zymurgy", "Accounts", "Marketing"]
+        );
+    }
+
+    #[test]
+    fn test_retrieve_all_employees_paged() {
+        let store = store_added_in_order(vec![("Bob", "A"), ("Bob", "B"), ("Bob", "C"), ("Bob", "D"), ("Bob", "E")]);
+        let page = |number| ListingOptions {
+            sort_key: SortKey::Name, descending: false, page: Some(PageRequest { number, size: 2 }),
+        };
+        let last_page = store.retrieve_all_employees_with_options(&page(3));
+        assert_eq!(department_names(&last_page), vec!["E"]);
+        assert_eq!((last_page.page_number, last_page.page_count, last_page.total_items), (3, 3, 5));
+        assert_eq!(department_names(&store.retrieve_all_employees_with_options(&page(4))), Vec::<&str>::new());
+
+        let huge_page = store.retrieve_all_employees_with_options(&page(usize::MAX));
+        assert_eq!(department_names(&huge_page), Vec::<&str>::new());
+        assert_eq!((huge_page.page_number, huge_page.page_count, huge_page.total_items), (usize::MAX, 3, 5));
+        let huge_size = ListingOptions { page: Some(PageRequest { number: 1, size: usize::MAX }), ..page(1) };
+        let whole_roster = store.retrieve_all_employees_with_options(&huge_size);
+        assert_eq!(department_names(&whole_roster), vec!["A", "B", "C", "D", "E"]);
+    }
+
+    #[test]
+    fn test_retrieve_employees_by_department_sorted_by_date_added_descending() {
+        let store = store_added_in_order(vec![("Weebl", "Pies"), ("Bob", "Pies"), ("Chris", "Pies")]);
+        let options = ListingOptions {
+            sort_key: SortKey::DateAdded, descending: true, page: Some(PageRequest { number: 1, size: 2 }),
+        };
+        assert_eq!(
+            store.retrieve_employees_by_department_with_options(&"Pies".to_string(), &options),
+            Some(ListingPage {
+                items: vec!["Chris".to_string(), "Bob".to_string()], page_number: 1, page_count: 2, total_items: 3,
+            })
+        );
+        assert_eq!(store.retrieve_employees_by_department_with_options(&"Cakes".to_string(), &options), None);
+    }
+
+    #[test]
+    fn test_conforms_with_every_starting_policy() {
+        crate::employee_store::conformance::check_conformance(EmployeeStoreImpl::new);
+        crate::employee_store::conformance::check_conformance(
+            || EmployeeStoreImpl::with_empty_department_policy(EmptyDepartmentPolicy::AutoDelete)
+        );
+    }
+
+    #[test]
+    fn test_boxed_store_conforms() {
+        crate::employee_store::conformance::check_conformance(
+            || Box::new(EmployeeStoreImpl::new()) as Box<dyn EmployeeStore>
+        );
+    }
 }
@@ -0,0 +1,224 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    DepartmentInfo, EmployeeDeletionResult, EmployeeStore, EmployeeStoreImpl, EmptyDepartmentPolicy, ListingOptions,
+    ListingPage,
+};
+
+// Keeps the roster in memory, writing the whole of it back to a JSON file after every change. The order things were
+//  added in is not saved.
+pub struct JsonFileEmployeeStore {
+    store: EmployeeStoreImpl,
+    file_path: PathBuf,
+    // Set when the last save failed, so the next flush tries again (and reports it if it fails again)
+    unsaved: bool,
+}
+
+#[derive(Serialize)]
+struct SavedRoster<'a> {
+    empty_department_policy: &'a str,
+    departments: Vec<DepartmentInfo>,
+}
+
+// Files saved before the policy was kept are just the list of departments, in the same format as snapshots
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LoadedRoster {
+    WithPolicy { empty_department_policy: String, departments: Vec<DepartmentInfo> },
+    DepartmentsOnly(Vec<DepartmentInfo>),
+}
+
+impl JsonFileEmployeeStore {
+    // A missing file is treated as an empty roster, and is created on the first change
+    pub fn open(file_path: PathBuf) -> Result<JsonFileEmployeeStore, String> {
+        let mut store = EmployeeStoreImpl::new();
+        let roster = match fs::read_to_string(&file_path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|e| format!("Could not parse roster file \"{}\" - {}", file_path.display(), e))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => LoadedRoster::DepartmentsOnly(vec![]),
+            Err(e) => return Err(format!("Could not read roster file \"{}\" - {}", file_path.display(), e))
+        };
+        let (policy, departments) = match roster {
+            LoadedRoster::WithPolicy { empty_department_policy, departments } => {
+                let policy = EmptyDepartmentPolicy::from_name(&empty_department_policy).ok_or(format!(
+                    "Unknown empty department policy \"{}\" in roster file \"{}\"",
+                    empty_department_policy, file_path.display()
+                ))?;
+                (policy, departments)
+            }
+            LoadedRoster::DepartmentsOnly(departments) => (store.get_empty_department_policy(), departments),
+        };
+        for info in &departments {
+            store.create_department(&info.department)?;
+            for employee_name in &info.employee_names {
+                store.add_employee(employee_name, &info.department);
+            }
+        }
+        store.set_empty_department_policy(policy);
+        Ok(JsonFileEmployeeStore { store, file_path, unsaved: false })
+    }
+
+    // A failed write leaves the change made in memory only - it is logged here, and reported by the next flush
+    fn save(&mut self) -> Result<(), String> {
+        let roster = SavedRoster {
+            empty_department_policy: self.store.get_empty_department_policy().name(),
+            departments: self.store.retrieve_all_employees(),
+        };
+        // Serialization can only fail for non-string map keys or failing Serialize impls, neither of which apply here
+        let json = serde_json::to_string_pretty(&roster).unwrap();
+        let result = fs::write(&self.file_path, json)
+            .map_err(|e| format!("Could not save roster to \"{}\" - {}", self.file_path.display(), e));
+        if let Err(e) = &result {
+            error!("{}", e);
+        }
+        self.unsaved = result.is_err();
+        result
+    }
+}
+
+impl EmployeeStore for JsonFileEmployeeStore {
+    fn add_employee(&mut self, employee_name: &String, department: &String) {
+        self.store.add_employee(employee_name, department);
+        let _ = self.save();
+    }
+
+    fn retrieve_employees_by_department(&self, department: &String) -> Option<Vec<String>> {
+        self.store.retrieve_employees_by_department(department)
+    }
+
+    fn retrieve_all_employees(&self) -> Vec<DepartmentInfo> {
+        self.store.retrieve_all_employees()
+    }
+
+    fn retrieve_all_employees_with_options(&self, options: &ListingOptions) -> ListingPage<DepartmentInfo> {
+        self.store.retrieve_all_employees_with_options(options)
+    }
+
+    fn retrieve_employees_by_department_with_options(&self, department: &String, options: &ListingOptions)
+        -> Option<ListingPage<String>>
+    {
+        self.store.retrieve_employees_by_department_with_options(department, options)
+    }
+
+    fn list_departments(&self) -> Vec<String> {
+        self.store.list_departments()
+    }
+
+    fn delete_department(&mut self, department: &String) -> Result<DepartmentInfo, String> {
+        let deleted_department = self.store.delete_department(department)?;
+        let _ = self.save();
+        Ok(deleted_department)
+    }
+
+    fn delete_employee(&mut self, employee_name: &String, department: &String) -> EmployeeDeletionResult {
+        let result = self.store.delete_employee(employee_name, department);
+        if result == EmployeeDeletionResult::SuccessfullyDeleted {
+            let _ = self.save();
+        }
+        result
+    }
+
+    fn create_department(&mut self, department: &String) -> Result<(), String> {
+        self.store.create_department(department)?;
+        let _ = self.save();
+        Ok(())
+    }
+
+    fn get_empty_department_policy(&self) -> EmptyDepartmentPolicy {
+        self.store.get_empty_department_policy()
+    }
+
+    fn set_empty_department_policy(&mut self, policy: EmptyDepartmentPolicy) -> Vec<String> {
+        let removed_departments = self.store.set_empty_department_policy(policy);
+        let _ = self.save();
+        removed_departments
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        if self.unsaved {
+            self.save()?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::JsonFileEmployeeStore;
+    use crate::employee_store::{DepartmentInfo, EmployeeStore, EmptyDepartmentPolicy};
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_changes_are_saved_and_reloaded() {
+        let path = env::temp_dir().join(format!("employee-management-roster-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut store = JsonFileEmployeeStore::open(path.clone()).unwrap();
+        store.add_employee(&"Bob".to_string(), &"Pies".to_string());
+        store.create_department(&"Pie Rejection".to_string()).unwrap();
+        let reopened = JsonFileEmployeeStore::open(path.clone()).unwrap().retrieve_all_employees();
+        fs::write(&path, "not json").unwrap();
+        let invalid = JsonFileEmployeeStore::open(path.clone());
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(reopened, store.retrieve_all_employees());
+        assert_eq!(reopened.len(), 2);
+        assert!(invalid.err().unwrap().starts_with("Could not parse roster file"));
+    }
+
+    #[test]
+    fn test_empty_department_policy_is_saved_and_older_files_still_load() {
+        let path = env::temp_dir().join(format!("employee-management-policy-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut store = JsonFileEmployeeStore::open(path.clone()).unwrap();
+        store.create_department(&"Pies".to_string()).unwrap();
+        store.set_empty_department_policy(EmptyDepartmentPolicy::RequireExplicitCreate);
+        let reopened = JsonFileEmployeeStore::open(path.clone()).unwrap();
+        let departments = vec![DepartmentInfo { department: "Pies".to_string(), employee_names: vec![] }];
+        fs::write(&path, serde_json::to_string(&departments).unwrap()).unwrap();
+        let older = JsonFileEmployeeStore::open(path.clone()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(reopened.get_empty_department_policy(), EmptyDepartmentPolicy::RequireExplicitCreate);
+        assert_eq!(reopened.list_departments(), vec!["Pies".to_string()]);
+        assert_eq!(older.get_empty_department_policy(), EmptyDepartmentPolicy::Keep);
+        assert_eq!(older.retrieve_all_employees(), departments);
+    }
+
+    #[test]
+    fn test_failed_save_is_reported_by_flush_until_it_succeeds() {
+        let directory = env::temp_dir().join(format!("employee-management-unsaved-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let path = directory.join("roster.json");
+
+        let mut store = JsonFileEmployeeStore::open(path.clone()).unwrap();
+        assert_eq!(store.flush(), Ok(()));
+        store.add_employee(&"Bob".to_string(), &"Pies".to_string());
+        let failed_flush = store.flush();
+        fs::create_dir_all(&directory).unwrap();
+        let retried_flush = store.flush();
+        let reopened = JsonFileEmployeeStore::open(path.clone()).unwrap().retrieve_all_employees();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(failed_flush.unwrap_err().starts_with("Could not save roster to"));
+        assert_eq!(retried_flush, Ok(()));
+        assert_eq!(reopened, store.retrieve_all_employees());
+    }
+
+    #[test]
+    fn test_conforms() {
+        let path = env::temp_dir().join(format!("employee-management-conformance-{}.json", std::process::id()));
+        crate::employee_store::conformance::check_conformance(|| {
+            let _ = fs::remove_file(&path);
+            JsonFileEmployeeStore::open(path.clone()).unwrap()
+        });
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -1,12 +1,19 @@
 use std::fmt::Debug;
 
 use mockall_derive::automock;
+use serde::{Deserialize, Serialize};
 
 pub use implementation::EmployeeStoreImpl;
+pub use json_file::JsonFileEmployeeStore;
+pub use observable::{ObservableEmployeeStore, StoreEvent, StoreEventCallback};
 
+#[cfg(test)]
+mod conformance;
 mod implementation;
+mod json_file;
+mod observable;
 
-#[derive(Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Serialize, Deserialize)]
 pub struct DepartmentInfo {
     pub department: String,
     pub employee_names: Vec<String>,
@@ -19,17 +26,172 @@ pub enum EmployeeDeletionResult {
     EmployeeNotInDepartment,
 }
 
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum EmptyDepartmentPolicy {
+    // Remove a department as soon as its last employee is deleted
+    AutoDelete,
+    // Leave departments in place (with no employees) until they are explicitly deleted
+    Keep,
+    // As for Keep, but employees can only be added to departments that were created with "Create department"
+    RequireExplicitCreate,
+}
+
+impl EmptyDepartmentPolicy {
+    pub const NAMES: [&'static str; 3] = ["auto-delete", "keep", "require-create"];
+
+    pub fn from_name(name: &str) -> Option<EmptyDepartmentPolicy> {
+        match name {
+            "auto-delete" => Some(EmptyDepartmentPolicy::AutoDelete),
+            "keep" => Some(EmptyDepartmentPolicy::Keep),
+            "require-create" => Some(EmptyDepartmentPolicy::RequireExplicitCreate),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EmptyDepartmentPolicy::AutoDelete => "auto-delete",
+            EmptyDepartmentPolicy::Keep => "keep",
+            EmptyDepartmentPolicy::RequireExplicitCreate => "require-create",
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum SortKey {
+    Name,
+    // Number of employees - only meaningful when listing departments
+    Size,
+    DateAdded,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct PageRequest {
+    // Page numbers start from 1
+    pub number: usize,
+    pub size: usize,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct ListingOptions {
+    pub sort_key: SortKey,
+    pub descending: bool,
+    pub page: Option<PageRequest>,
+}
+
+impl ListingOptions {
+    pub fn new() -> ListingOptions {
+        ListingOptions { sort_key: SortKey::Name, descending: false, page: None }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug)]
+pub struct ListingPage<T> {
+    pub items: Vec<T>,
+    pub page_number: usize,
+    pub page_count: usize,
+    pub total_items: usize,
+}
+
+// Cuts already-sorted items down to the requested page, for stores that can't do this as part of their query.
+//  A page past the end is returned empty rather than as an error, as the roster may have shrunk since it was counted.
+pub fn paginate<T>(mut items: Vec<T>, page: Option<PageRequest>) -> ListingPage<T> {
+    let total_items = items.len();
+    match page {
+        None => ListingPage { items, page_number: 1, page_count: 1, total_items },
+        Some(PageRequest { number, size }) => {
+            let page_count = std::cmp::max(1, total_items.div_ceil(size));
+            // Page numbers and sizes can be as big as the user likes - a page too far along to count is past the end
+            let start = (number - 1).checked_mul(size).map_or(total_items, |start| std::cmp::min(start, total_items));
+            let end = std::cmp::min(start.saturating_add(size), total_items);
+            ListingPage { items: items.drain(start..end).collect(), page_number: number, page_count, total_items }
+        }
+    }
+}
+
 #[automock]
 pub trait EmployeeStore {
+    // Creates the department if needed - adding an employee who is already in the department changes nothing
     fn add_employee(&mut self, employee_name: &String, department: &String);
 
     fn retrieve_employees_by_department(&self, department: &String) -> Option<Vec<String>>;
 
     fn retrieve_all_employees(&self) -> Vec<DepartmentInfo>;
 
+    // Options sort departments (employee names within each stay in alphabetical order)
+    fn retrieve_all_employees_with_options(&self, options: &ListingOptions) -> ListingPage<DepartmentInfo>;
+
+    // Sorting employees by size is not meaningful, and falls back to sorting by name
+    fn retrieve_employees_by_department_with_options(&self, department: &String, options: &ListingOptions)
+        -> Option<ListingPage<String>>;
+
     fn list_departments(&self) -> Vec<String>;
 
     fn delete_department(&mut self, department: &String) -> Result<DepartmentInfo, String>;
 
     fn delete_employee(&mut self, employee_name: &String, department: &String) -> EmployeeDeletionResult;
+
+    fn create_department(&mut self, department: &String) -> Result<(), String>;
+
+    fn get_empty_department_policy(&self) -> EmptyDepartmentPolicy;
+
+    // Returns any departments removed because the new policy no longer allows them to be empty
+    fn set_empty_department_policy(&mut self, policy: EmptyDepartmentPolicy) -> Vec<String>;
+
+    // Checks every change so far has been stored - stores that save elsewhere report a failure to do so here, the
+    //  changes themselves having already been made in memory
+    fn flush(&mut self) -> Result<(), String>;
+}
+
+// Lets the store backend be chosen at run time (from the settings), while everything else stays generic over the store
+impl EmployeeStore for Box<dyn EmployeeStore> {
+    fn add_employee(&mut self, employee_name: &String, department: &String) {
+        self.as_mut().add_employee(employee_name, department)
+    }
+
+    fn retrieve_employees_by_department(&self, department: &String) -> Option<Vec<String>> {
+        self.as_ref().retrieve_employees_by_department(department)
+    }
+
+    fn retrieve_all_employees(&self) -> Vec<DepartmentInfo> {
+        self.as_ref().retrieve_all_employees()
+    }
+
+    fn retrieve_all_employees_with_options(&self, options: &ListingOptions) -> ListingPage<DepartmentInfo> {
+        self.as_ref().retrieve_all_employees_with_options(options)
+    }
+
+    fn retrieve_employees_by_department_with_options(&self, department: &String, options: &ListingOptions)
+        -> Option<ListingPage<String>>
+    {
+        self.as_ref().retrieve_employees_by_department_with_options(department, options)
+    }
+
+    fn list_departments(&self) -> Vec<String> {
+        self.as_ref().list_departments()
+    }
+
+    fn delete_department(&mut self, department: &String) -> Result<DepartmentInfo, String> {
+        self.as_mut().delete_department(department)
+    }
+
+    fn delete_employee(&mut self, employee_name: &String, department: &String) -> EmployeeDeletionResult {
+        self.as_mut().delete_employee(employee_name, department)
+    }
+
+    fn create_department(&mut self, department: &String) -> Result<(), String> {
+        self.as_mut().create_department(department)
+    }
+
+    fn get_empty_department_policy(&self) -> EmptyDepartmentPolicy {
+        self.as_ref().get_empty_department_policy()
+    }
+
+    fn set_empty_department_policy(&mut self, policy: EmptyDepartmentPolicy) -> Vec<String> {
+        self.as_mut().set_empty_department_policy(policy)
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        self.as_mut().flush()
+    }
 }
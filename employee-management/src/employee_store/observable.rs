@@ -0,0 +1,217 @@
+use std::sync::mpsc::{channel, Receiver};
+
+use super::{
+    DepartmentInfo, EmployeeDeletionResult, EmployeeStore, EmptyDepartmentPolicy, ListingOptions, ListingPage,
+};
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum StoreEvent {
+    EmployeeAdded { employee_name: String, department: String },
+    EmployeeDeleted { employee_name: String, department: String },
+    DepartmentCreated { department: String },
+    // Departments removed because they became empty under the auto-delete policy are reported with no employees
+    DepartmentDeleted(DepartmentInfo),
+}
+
+impl StoreEvent {
+    pub const NAMES: [&'static str; 4] = ["employee-added", "employee-deleted", "department-created", "department-deleted"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StoreEvent::EmployeeAdded { .. } => "employee-added",
+            StoreEvent::EmployeeDeleted { .. } => "employee-deleted",
+            StoreEvent::DepartmentCreated { .. } => "department-created",
+            StoreEvent::DepartmentDeleted(_) => "department-deleted",
+        }
+    }
+}
+
+pub type StoreEventCallback = Box<dyn FnMut(&StoreEvent)>;
+
+// Wraps any store, notifying subscribers of each change after it has been made. Subscribers are called in the order
+//  they subscribed, on the thread making the change - anything slow should be done via a channel instead.
+pub struct ObservableEmployeeStore<S: EmployeeStore> {
+    store: S,
+    subscribers: Vec<StoreEventCallback>,
+}
+
+impl<S: EmployeeStore> ObservableEmployeeStore<S> {
+    pub fn new(store: S) -> ObservableEmployeeStore<S> {
+        ObservableEmployeeStore { store, subscribers: vec![] }
+    }
+
+    pub fn subscribe(&mut self, callback: StoreEventCallback) {
+        self.subscribers.push(callback);
+    }
+
+    // Events sent after the receiver has been dropped are discarded
+    pub fn subscribe_channel(&mut self) -> Receiver<StoreEvent> {
+        let (sender, receiver) = channel();
+        self.subscribe(Box::new(move |event: &StoreEvent| {
+            let _ = sender.send(event.clone());
+        }));
+        receiver
+    }
+
+    fn notify(&mut self, event: StoreEvent) {
+        debug!("Notifying {} subscribers of {:?}", self.subscribers.len(), event);
+        for subscriber in self.subscribers.iter_mut() {
+            subscriber(&event);
+        }
+    }
+
+    fn notify_departments_removed(&mut self, departments: &Vec<String>) {
+        for department in departments {
+            self.notify(StoreEvent::DepartmentDeleted(
+                DepartmentInfo { department: department.clone(), employee_names: vec![] }
+            ));
+        }
+    }
+}
+
+impl<S: EmployeeStore> EmployeeStore for ObservableEmployeeStore<S> {
+    fn add_employee(&mut self, employee_name: &String, department: &String) {
+        let existing_employees = self.store.retrieve_employees_by_department(department);
+        self.store.add_employee(employee_name, department);
+        match existing_employees {
+            Some(employee_names) if employee_names.contains(employee_name) => return,
+            Some(_) => {}
+            None => self.notify(StoreEvent::DepartmentCreated { department: department.clone() })
+        }
+        self.notify(StoreEvent::EmployeeAdded { employee_name: employee_name.clone(), department: department.clone() });
+    }
+
+    fn retrieve_employees_by_department(&self, department: &String) -> Option<Vec<String>> {
+        self.store.retrieve_employees_by_department(department)
+    }
+
+    fn retrieve_all_employees(&self) -> Vec<DepartmentInfo> {
+        self.store.retrieve_all_employees()
+    }
+
+    fn retrieve_all_employees_with_options(&self, options: &ListingOptions) -> ListingPage<DepartmentInfo> {
+        self.store.retrieve_all_employees_with_options(options)
+    }
+
+    fn retrieve_employees_by_department_with_options(&self, department: &String, options: &ListingOptions)
+        -> Option<ListingPage<String>>
+    {
+        self.store.retrieve_employees_by_department_with_options(department, options)
+    }
+
+    fn list_departments(&self) -> Vec<String> {
+        self.store.list_departments()
+    }
+
+    fn delete_department(&mut self, department: &String) -> Result<DepartmentInfo, String> {
+        let deleted_department = self.store.delete_department(department)?;
+        self.notify(StoreEvent::DepartmentDeleted(deleted_department.clone()));
+        Ok(deleted_department)
+    }
+
+    fn delete_employee(&mut self, employee_name: &String, department: &String) -> EmployeeDeletionResult {
+        let result = self.store.delete_employee(employee_name, department);
+        if result == EmployeeDeletionResult::SuccessfullyDeleted {
+            self.notify(StoreEvent::EmployeeDeleted {
+                employee_name: employee_name.clone(), department: department.clone(),
+            });
+            if self.store.retrieve_employees_by_department(department).is_none() {
+                self.notify_departments_removed(&vec![department.clone()]);
+            }
+        }
+        result
+    }
+
+    fn create_department(&mut self, department: &String) -> Result<(), String> {
+        self.store.create_department(department)?;
+        self.notify(StoreEvent::DepartmentCreated { department: department.clone() });
+        Ok(())
+    }
+
+    fn get_empty_department_policy(&self) -> EmptyDepartmentPolicy {
+        self.store.get_empty_department_policy()
+    }
+
+    fn set_empty_department_policy(&mut self, policy: EmptyDepartmentPolicy) -> Vec<String> {
+        let removed_departments = self.store.set_empty_department_policy(policy);
+        self.notify_departments_removed(&removed_departments);
+        removed_departments
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        self.store.flush()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{ObservableEmployeeStore, StoreEvent};
+    use crate::employee_store::{DepartmentInfo, EmployeeStore, EmployeeStoreImpl, EmptyDepartmentPolicy};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn strings(values: Vec<&str>) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_channel_receives_events_in_order() {
+        let mut store = ObservableEmployeeStore::new(EmployeeStoreImpl::new());
+        let events = store.subscribe_channel();
+
+        store.add_employee(&"Bob".to_string(), &"Pies".to_string());
+        store.add_employee(&"Weebl".to_string(), &"Pies".to_string());
+        store.delete_employee(&"Bob".to_string(), &"Pies".to_string());
+        store.delete_department(&"Pies".to_string()).unwrap();
+
+        assert_eq!(events.try_iter().collect::<Vec<StoreEvent>>(), vec![
+            StoreEvent::DepartmentCreated { department: "Pies".to_string() },
+            StoreEvent::EmployeeAdded { employee_name: "Bob".to_string(), department: "Pies".to_string() },
+            StoreEvent::EmployeeAdded { employee_name: "Weebl".to_string(), department: "Pies".to_string() },
+            StoreEvent::EmployeeDeleted { employee_name: "Bob".to_string(), department: "Pies".to_string() },
+            StoreEvent::DepartmentDeleted(
+                DepartmentInfo { department: "Pies".to_string(), employee_names: strings(vec!["Weebl"]) }
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_failed_changes_are_not_reported() {
+        let mut store = ObservableEmployeeStore::new(EmployeeStoreImpl::new());
+        let events = store.subscribe_channel();
+
+        assert!(store.delete_department(&"Pies".to_string()).is_err());
+        store.delete_employee(&"Bob".to_string(), &"Pies".to_string());
+
+        assert_eq!(events.try_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_reports_departments_removed_by_auto_delete_policy() {
+        let mut store = ObservableEmployeeStore::new(EmployeeStoreImpl::new());
+        store.create_department(&"Pie Rejection".to_string()).unwrap();
+        store.add_employee(&"Bob".to_string(), &"Pies".to_string());
+        let received: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(vec![]));
+        let callback_received = received.clone();
+        store.subscribe(Box::new(move |event: &StoreEvent| {
+            if let StoreEvent::DepartmentDeleted(info) = event {
+                callback_received.borrow_mut().push(info.department.clone());
+            }
+        }));
+
+        store.set_empty_department_policy(EmptyDepartmentPolicy::AutoDelete);
+        store.delete_employee(&"Bob".to_string(), &"Pies".to_string());
+
+        assert_eq!(*received.borrow(), strings(vec!["Pie Rejection", "Pies"]));
+    }
+
+    #[test]
+    fn test_conforms() {
+        crate::employee_store::conformance::check_conformance(|| {
+            let mut store = ObservableEmployeeStore::new(EmployeeStoreImpl::new());
+            store.subscribe(Box::new(|_| {}));
+            store
+        });
+    }
+}
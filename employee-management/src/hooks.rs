@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::config_file::read_key_value_file;
+use crate::employee_store::{StoreEvent, StoreEventCallback};
+
+pub const DEFAULT_HOOKS_FILE: &'static str = "hooks.conf";
+
+// Runs a local shell command for each roster change, configured as "event-name = command" lines (e.g.
+//  "employee-added = ./regenerate-team-page.sh"). Details of the event are passed in environment variables, so
+//  that names never need quoting in the command itself.
+#[derive(Eq, PartialEq, Debug, Default)]
+pub struct ShellCommandSubscriber {
+    commands: BTreeMap<String, String>,
+}
+
+impl ShellCommandSubscriber {
+    pub fn new(commands: BTreeMap<String, String>) -> ShellCommandSubscriber {
+        ShellCommandSubscriber { commands }
+    }
+
+    pub fn load(file_path: &Path) -> Result<ShellCommandSubscriber, String> {
+        let mut commands = BTreeMap::new();
+        for (event_name, command) in read_key_value_file(file_path)? {
+            if !StoreEvent::NAMES.contains(&event_name.as_str()) {
+                return Err(format!(
+                    "Unknown event \"{}\" in \"{}\" - expected one of {}",
+                    event_name, file_path.display(), StoreEvent::NAMES.join(", ")
+                ));
+            }
+            commands.insert(event_name, command);
+        }
+        Ok(ShellCommandSubscriber::new(commands))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    fn environment(event: &StoreEvent) -> Vec<(&'static str, String)> {
+        let mut variables = vec![("HOOK_EVENT", event.name().to_string())];
+        match event {
+            StoreEvent::EmployeeAdded { employee_name, department }
+            | StoreEvent::EmployeeDeleted { employee_name, department } => {
+                variables.push(("EMPLOYEE_NAME", employee_name.clone()));
+                variables.push(("DEPARTMENT", department.clone()));
+            }
+            StoreEvent::DepartmentCreated { department } => {
+                variables.push(("DEPARTMENT", department.clone()));
+            }
+            StoreEvent::DepartmentDeleted(info) => {
+                variables.push(("DEPARTMENT", info.department.clone()));
+                variables.push(("EMPLOYEE_NAMES", info.employee_names.join("\n")));
+            }
+        }
+        variables
+    }
+
+    // A failing hook is logged rather than reported as an error, as the change it describes has already been made
+    pub fn run_hook(&self, event: &StoreEvent) {
+        let command = match self.commands.get(event.name()) {
+            Some(command) => command,
+            None => return
+        };
+        debug!("Running {} hook \"{}\"", event.name(), command);
+        match Command::new("sh").arg("-c").arg(command).envs(ShellCommandSubscriber::environment(event)).status() {
+            Ok(status) if status.success() => {}
+            Ok(status) => warn!("Hook \"{}\" for {} failed with {}", command, event.name(), status),
+            Err(e) => warn!("Could not run hook \"{}\" for {} - {}", command, event.name(), e),
+        }
+    }
+
+    pub fn into_callback(self) -> StoreEventCallback {
+        Box::new(move |event: &StoreEvent| self.run_hook(event))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::ShellCommandSubscriber;
+    use crate::employee_store::{DepartmentInfo, StoreEvent};
+    use std::collections::BTreeMap;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_load_rejects_unknown_event() {
+        let path = env::temp_dir().join(format!("employee-management-hooks-{}.conf", std::process::id()));
+        fs::write(&path, "# Hooks\nemployee-added = true\n").unwrap();
+        let loaded = ShellCommandSubscriber::load(&path);
+        fs::write(&path, "employee-promoted = true\n").unwrap();
+        let invalid = ShellCommandSubscriber::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        let mut expected_commands = BTreeMap::new();
+        expected_commands.insert("employee-added".to_string(), "true".to_string());
+        assert_eq!(loaded, Ok(ShellCommandSubscriber::new(expected_commands)));
+        assert!(invalid.unwrap_err().starts_with("Unknown event \"employee-promoted\""));
+    }
+
+    #[test]
+    fn test_runs_command_for_event_with_details_in_environment() {
+        let output_path = env::temp_dir().join(format!("employee-management-hook-output-{}", std::process::id()));
+        let mut commands = BTreeMap::new();
+        commands.insert(
+            "department-deleted".to_string(),
+            format!("printf '%s|%s|%s' \"$HOOK_EVENT\" \"$DEPARTMENT\" \"$EMPLOYEE_NAMES\" > {}", output_path.display()),
+        );
+        let subscriber = ShellCommandSubscriber::new(commands);
+
+        subscriber.run_hook(&StoreEvent::DepartmentCreated { department: "Pies".to_string() });
+        assert!(!output_path.exists());
+
+        subscriber.run_hook(&StoreEvent::DepartmentDeleted(DepartmentInfo {
+            department: "Pie Rejection".to_string(),
+            employee_names: vec!["Bob".to_string(), "Weebl".to_string()],
+        }));
+        let output = fs::read_to_string(&output_path).unwrap();
+        fs::remove_file(&output_path).unwrap();
+
+        assert_eq!(output, "department-deleted|Pie Rejection|Bob\nWeebl");
+    }
+}
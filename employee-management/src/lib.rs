@@ -2,21 +2,35 @@
 extern crate log;
 extern crate simple_logger;
 
+mod access;
 mod command;
+mod config_file;
 mod employee_store;
+mod hooks;
+mod locale;
+mod report;
+mod settings;
+mod snapshot;
 
+use std::fs::OpenOptions;
 use std::io;
+use std::io::Write;
+use std::path::Path;
 use command::{HandleCommand, CommandDispatcher};
 use employee_store::EmployeeStore;
+use locale::text;
+use settings::OutputFormat;
 
 pub use command::get_command_dispatcher;
+pub use settings::{parse_command_line, Settings, USAGE_TEXT};
 
 fn show_usage<E: 'static + EmployeeStore, H: HandleCommand<E>>(dispatcher: &CommandDispatcher<E, H>) {
     info!("Showing usage");
     println!("\n{}", dispatcher.get_usage_text());
-    println!("Alternatively, enter:");
-    println!(" - \"Help\" to show this usage info");
-    println!(" - \"Quit\" to exit the program\n");
+    println!("{}", dispatcher.get_alias_usage_text());
+    println!("{}", text("session.alternatives"));
+    println!(" - \"{}\" {}", text("session.help_command"), text("session.help_description"));
+    println!(" - \"{}\" {}\n", text("session.quit_command"), text("session.quit_description"));
 }
 
 fn get_string(message: &str) -> io::Result<String> {
@@ -26,31 +40,95 @@ fn get_string(message: &str) -> io::Result<String> {
     Ok(buffer)
 }
 
-pub fn run<E: 'static + EmployeeStore, H: HandleCommand<E>>(dispatcher: &mut CommandDispatcher<E, H>) -> Result<(), String> {
+fn check_for_ambiguous_commands<E: 'static + EmployeeStore, H: HandleCommand<E>>(dispatcher: &CommandDispatcher<E, H>) {
+    info!("Checking command formats for ambiguity");
+    for ambiguity in dispatcher.find_ambiguities() {
+        warn!("{} - such commands will be rejected as ambiguous", ambiguity);
+    }
+}
+
+const MAX_LOGIN_ATTEMPTS: usize = 3;
 
+fn log_in<E: 'static + EmployeeStore, H: HandleCommand<E>>(dispatcher: &mut CommandDispatcher<E, H>) -> Result<(), String> {
+    if !dispatcher.access_control_enabled() {
+        warn!("Access control is disabled - all commands are permitted");
+        return Ok(());
+    }
+    for _ in 0..MAX_LOGIN_ATTEMPTS {
+        let user_name = get_string(&text("session.user_name_prompt"))
+            .map_err(|e| format!("There was an error reading stdin: {:?}", e))?;
+        match dispatcher.log_in(user_name.trim()) {
+            Ok(msg) => {
+                info!("{}", msg);
+                return Ok(());
+            }
+            Err(msg) => error!("{}", msg)
+        }
+    }
+    Err(format!("Login failed after {} attempts", MAX_LOGIN_ATTEMPTS))
+}
+
+// History is a convenience, so failing to write it is logged rather than stopping the session
+fn append_to_history(history_file: &Path, text_command: &str) {
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_file)
+        .and_then(|mut file| writeln!(file, "{}", text_command));
+    if let Err(e) = result {
+        warn!("Could not write to history file \"{}\" - {}", history_file.display(), e);
+    }
+}
+
+fn report_result(text_command: &str, result: Result<String, String>, output_format: OutputFormat) {
+    match (output_format, result) {
+        (OutputFormat::Text, Ok(msg)) =>
+            debug!("Command \"{}\" processed okay - {}", text_command, msg),
+        (OutputFormat::Text, Err(msg)) => {
+            error!("{}", msg);
+            error!("Error processing command \"{}\", please try again", text_command);
+        }
+        (OutputFormat::Json, result) => {
+            let (succeeded, message) = match result {
+                Ok(msg) => (true, msg),
+                Err(msg) => (false, msg)
+            };
+            println!("{}", serde_json::json!({ "command": text_command, "succeeded": succeeded, "message": message }));
+        }
+    }
+}
+
+pub fn run<E: 'static + EmployeeStore, H: HandleCommand<E>>(
+    dispatcher: &mut CommandDispatcher<E, H>, settings: &Settings
+) -> Result<(), String> {
+
+    check_for_ambiguous_commands(dispatcher);
+    log_in(dispatcher)?;
     show_usage(&dispatcher);
+    if settings.dry_run {
+        warn!("Dry run - commands will report what they would change without changing anything");
+    }
 
+    let prompt = settings.prompt.clone().unwrap_or(text("session.prompt"));
+    let (help_command, quit_command) = (text("session.help_command"), text("session.quit_command"));
     loop {
-        match get_string("Please enter a text command:") {
+        match get_string(&prompt) {
             Ok(raw_string) => {
 
                 let text_command: &str = &raw_string.trim()[..];
 
-                if text_command == "Quit" {
+                if let (Some(history_file), false) = (&settings.history_file, text_command.is_empty()) {
+                    append_to_history(history_file, text_command);
+                }
+
+                if text_command == quit_command {
                     return Ok(());
                 }
-                if text_command == "Help" {
+                if text_command == help_command {
                     show_usage(&dispatcher);
                 }
                 else {
-                    match dispatcher.process_command(text_command) {
-                        Ok(msg) =>
-                            debug!("Command \"{}\" processed okay - {}", text_command, msg),
-                        Err(msg) => {
-                            error!("{}", msg);
-                            error!("Error processing command \"{}\", please try again", text_command);
-                        }
-                    };
+                    report_result(text_command, dispatcher.process_command(text_command), settings.output_format);
                 }
             }
             Err(e) => {
@@ -0,0 +1,161 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::rc::Rc;
+
+use regex::{Captures, Regex};
+
+use crate::config_file::parse_key_value_text;
+
+pub const DEFAULT_LANGUAGE: &'static str = "en";
+pub const LANGUAGES: [&'static str; 2] = ["en", "fr"];
+const ARGUMENT_PATTERN: &'static str = r"\{(\w+)\}";
+
+fn catalog_text(language: &str) -> Option<&'static str> {
+    match language {
+        "en" => Some(include_str!("en.conf")),
+        "fr" => Some(include_str!("fr.conf")),
+        _ => None
+    }
+}
+
+// Message catalogs use the same "key = value" format as the other config files, and are built into the binary
+#[derive(Eq, PartialEq, Debug)]
+pub struct Catalog {
+    language: String,
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    // Keys missing from a catalog fall back to the English text, so a partial translation is still usable
+    pub fn for_language(language: &str) -> Result<Catalog, String> {
+        let text = catalog_text(language).ok_or(format!(
+            "Unknown language \"{}\" - expected one of {}", language, LANGUAGES.join(", ")
+        ))?;
+        let mut messages: HashMap<String, String> = if language == DEFAULT_LANGUAGE {
+            HashMap::new()
+        } else {
+            Catalog::for_language(DEFAULT_LANGUAGE)?.messages
+        };
+        for (key, message) in parse_key_value_text(text)? {
+            messages.insert(key, message);
+        }
+        Ok(Catalog { language: language.to_string(), messages })
+    }
+
+    // A missing key is a mistake in the code or the English catalog rather than something a user can cause, so (as
+    //  for mismatched handler args) it panics with a descriptive message.
+    pub fn text(&self, key: &str) -> String {
+        match self.messages.get(key) {
+            Some(message) => message.clone(),
+            None => panic!("No message \"{}\" in the \"{}\" catalog", key, self.language)
+        }
+    }
+
+    // Replaces each "{name}" in the message with the matching argument - any other braces are left as they are.
+    //  This is done in one pass over the message, so braces in argument values (which can come from the user) are
+    //  never themselves replaced.
+    pub fn message(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let message = self.text(key);
+        Regex::new(ARGUMENT_PATTERN).unwrap()
+            .replace_all(&message, |captures: &Captures| {
+                match args.iter().find(|(name, _)| *name == &captures[1]) {
+                    Some((_, value)) => value.to_string(),
+                    None => captures[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+}
+
+thread_local! {
+    static CURRENT_CATALOG: RefCell<Rc<Catalog>> =
+        RefCell::new(Rc::new(Catalog::for_language(DEFAULT_LANGUAGE).unwrap()));
+}
+
+// Chosen once at start-up, before the command handlers (whose patterns come from the catalog) are built
+pub fn set_language(language: &str) -> Result<(), String> {
+    let catalog = Catalog::for_language(language)?;
+    CURRENT_CATALOG.with(|current| *current.borrow_mut() = Rc::new(catalog));
+    Ok(())
+}
+
+fn current_catalog() -> Rc<Catalog> {
+    CURRENT_CATALOG.with(|current| current.borrow().clone())
+}
+
+pub fn text(key: &str) -> String {
+    current_catalog().text(key)
+}
+
+pub fn message(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    current_catalog().message(key, args)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{message, set_language, text, Catalog, LANGUAGES};
+    use regex::Regex;
+    use std::collections::BTreeSet;
+
+    fn capture_names(pattern: &str) -> BTreeSet<String> {
+        Regex::new(pattern).unwrap().capture_names().flatten().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_every_catalog_translates_every_key_with_matching_patterns() {
+        let english = Catalog::for_language("en").unwrap();
+        for language in LANGUAGES.iter() {
+            let own_keys: BTreeSet<String> = super::parse_key_value_text(super::catalog_text(language).unwrap())
+                .unwrap()
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect();
+            let catalog = Catalog::for_language(language).unwrap();
+            for key in english.messages.keys() {
+                assert!(own_keys.contains(key), "\"{}\" catalog has no \"{}\"", language, key);
+                if key.contains("pattern") {
+                    assert_eq!(capture_names(&catalog.text(key)), capture_names(&english.text(key)), "{}", key);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_message_substitutes_named_arguments() {
+        let catalog = Catalog::for_language("en").unwrap();
+        assert_eq!(
+            catalog.message("delete_employee.deleted", &[("employee_name", &"Bob"), ("department", &"Pies")]),
+            "Successfully deleted employee \"Bob\" from department \"Pies\""
+        );
+        assert_eq!(
+            catalog.message("alias_usage.example", &[]),
+            "e.g. \"Alias hire = Add {1} to {2}\", then \"hire Bob \"Pie QC\"\""
+        );
+    }
+
+    #[test]
+    fn test_message_leaves_braces_in_argument_values_alone() {
+        let catalog = Catalog::for_language("en").unwrap();
+        assert_eq!(
+            catalog.message("delete_employee.deleted", &[("employee_name", &"{department}"), ("department", &"Pies")]),
+            "Successfully deleted employee \"{department}\" from department \"Pies\""
+        );
+    }
+
+    #[test]
+    fn test_set_language_changes_current_catalog() {
+        assert_eq!(text("session.quit_command"), "Quit");
+        set_language("fr").unwrap();
+        assert_eq!(message("list_aliases.listed", &[("count", &2)]), "2 alias récupérés");
+        assert_eq!(set_language("xx"), Err("Unknown language \"xx\" - expected one of en, fr".to_string()));
+        set_language("en").unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "No message \"no.such.key\" in the \"en\" catalog")]
+    fn test_missing_key_panics() {
+        text("no.such.key");
+    }
+}
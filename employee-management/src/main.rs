@@ -1,13 +1,34 @@
 use employee_management;
+use employee_management::{Settings, USAGE_TEXT};
+use std::env;
 use std::process;
 
 fn main() {
 
-    simple_logger::init().unwrap();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command_line = employee_management::parse_command_line(&args).unwrap_or_else(|msg| {
+        eprintln!("{}\n\n{}", msg, USAGE_TEXT);
+        process::exit(2);
+    });
+    if command_line.show_help {
+        println!("{}", USAGE_TEXT);
+        return;
+    }
 
-    let mut dispatcher = employee_management::get_command_dispatcher();
+    // Settings on the command line override those in the config file, which override the defaults
+    let settings = Settings::load(command_line.config_file, command_line.settings).unwrap_or_else(|msg| {
+        eprintln!("Invalid settings: \"{}\"", msg);
+        process::exit(2);
+    });
 
-    if let Err(msg) = employee_management::run(&mut dispatcher) {
+    simple_logger::init_with_level(settings.log_level).unwrap();
+
+    let mut dispatcher = employee_management::get_command_dispatcher(&settings).unwrap_or_else(|msg| {
+        eprintln!("Start-up failed with error: \"{}\"", msg);
+        process::exit(1);
+    });
+
+    if let Err(msg) = employee_management::run(&mut dispatcher, &settings) {
         eprintln!("Execution failed with error: \"{}\"", msg);
         process::exit(1);
     };
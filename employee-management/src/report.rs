@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::employee_store::DepartmentInfo;
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct DepartmentHeadcount {
+    pub department: String,
+    pub headcount: usize,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct DuplicatedEmployee {
+    pub employee_name: String,
+    pub departments: Vec<String>,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct NameLengthStatistics {
+    pub shortest: usize,
+    pub longest: usize,
+    pub mean: f64,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct RosterStatistics {
+    pub departments: Vec<DepartmentHeadcount>,
+    pub total_employees: usize,
+    pub distinct_employees: usize,
+    pub largest_departments: Vec<String>,
+    pub smallest_departments: Vec<String>,
+    pub empty_departments: Vec<String>,
+    pub employees_in_multiple_departments: Vec<DuplicatedEmployee>,
+    // Lengths are in characters rather than bytes, and are only given when there is at least one employee
+    pub name_lengths: Option<NameLengthStatistics>,
+}
+
+fn departments_with_headcount(headcounts: &[DepartmentHeadcount], headcount: Option<usize>) -> Vec<String> {
+    match headcount {
+        Some(headcount) => headcounts
+            .iter()
+            .filter(|h| h.headcount == headcount)
+            .map(|h| h.department.clone())
+            .collect(),
+        None => vec![]
+    }
+}
+
+fn join_or_none(values: &[String]) -> String {
+    if values.is_empty() { "none".to_string() } else { values.join(", ") }
+}
+
+impl RosterStatistics {
+
+    pub fn from_departments(department_infos: &[DepartmentInfo]) -> RosterStatistics {
+        let departments: Vec<DepartmentHeadcount> = department_infos
+            .iter()
+            .map(|info| DepartmentHeadcount { department: info.department.clone(), headcount: info.employee_names.len() })
+            .collect();
+
+        let mut departments_by_employee: BTreeMap<&String, Vec<String>> = BTreeMap::new();
+        for info in department_infos {
+            for name in &info.employee_names {
+                departments_by_employee.entry(name).or_insert(vec![]).push(info.department.clone());
+            }
+        }
+
+        // Empty departments are reported separately, so only count as "smallest" if every department is empty
+        let non_empty_headcounts = departments.iter().map(|h| h.headcount).filter(|h| *h > 0);
+        let largest_headcount = departments.iter().map(|h| h.headcount).max();
+        let smallest_headcount = non_empty_headcounts.min().or(largest_headcount);
+
+        let name_lengths: Vec<usize> = departments_by_employee.keys().map(|name| name.chars().count()).collect();
+        let name_length_statistics = match (name_lengths.iter().min(), name_lengths.iter().max()) {
+            (Some(shortest), Some(longest)) => Some(NameLengthStatistics {
+                shortest: *shortest,
+                longest: *longest,
+                mean: name_lengths.iter().sum::<usize>() as f64 / name_lengths.len() as f64,
+            }),
+            _ => None
+        };
+
+        RosterStatistics {
+            total_employees: departments.iter().map(|h| h.headcount).sum(),
+            distinct_employees: departments_by_employee.len(),
+            largest_departments: departments_with_headcount(&departments, largest_headcount),
+            smallest_departments: departments_with_headcount(&departments, smallest_headcount),
+            empty_departments: departments_with_headcount(&departments, Some(0)),
+            employees_in_multiple_departments: departments_by_employee
+                .iter()
+                .filter(|(_, departments)| departments.len() > 1)
+                .map(|(name, departments)| DuplicatedEmployee {
+                    employee_name: name.to_string(), departments: departments.clone(),
+                })
+                .collect(),
+            name_lengths: name_length_statistics,
+            departments,
+        }
+    }
+
+    fn describe_extreme(&self, departments: &[String]) -> String {
+        match departments.first().and_then(|d| self.departments.iter().find(|h| &h.department == d)) {
+            Some(headcount) => format!("{} ({})", departments.join(", "), headcount.headcount),
+            None => "none".to_string()
+        }
+    }
+
+    pub fn render_text(&self) -> Vec<String> {
+        const DEPARTMENT_HEADING: &'static str = "Department";
+        const HEADCOUNT_HEADING: &'static str = "Headcount";
+        let department_width = self.departments
+            .iter()
+            .map(|h| h.department.chars().count())
+            .chain(vec![DEPARTMENT_HEADING.len()])
+            .max()
+            .unwrap();
+        let headcount_width = HEADCOUNT_HEADING.len();
+
+        let mut lines = vec![
+            format!("{:<dw$} | {:>hw$}", DEPARTMENT_HEADING, HEADCOUNT_HEADING, dw = department_width, hw = headcount_width),
+            format!("{}-+-{}", "-".repeat(department_width), "-".repeat(headcount_width)),
+        ];
+        for h in &self.departments {
+            lines.push(format!("{:<dw$} | {:>hw$}", h.department, h.headcount, dw = department_width, hw = headcount_width));
+        }
+
+        let duplicates: Vec<String> = self.employees_in_multiple_departments
+            .iter()
+            .map(|d| format!("{} ({})", d.employee_name, d.departments.join(", ")))
+            .collect();
+        lines.push(format!("Total employees: {} ({} distinct)", self.total_employees, self.distinct_employees));
+        lines.push(format!("Largest department(s): {}", self.describe_extreme(&self.largest_departments)));
+        lines.push(format!("Smallest department(s): {}", self.describe_extreme(&self.smallest_departments)));
+        lines.push(format!("Empty department(s): {}", join_or_none(&self.empty_departments)));
+        lines.push(format!("Employees in more than one department: {}", join_or_none(&duplicates)));
+        lines.push(match &self.name_lengths {
+            Some(n) => format!("Name length (characters): shortest {}, longest {}, mean {:.1}", n.shortest, n.longest, n.mean),
+            None => "Name length (characters): no employees".to_string()
+        });
+        lines
+    }
+
+    pub fn render_json(&self) -> String {
+        // Serialization can only fail for non-string map keys or failing Serialize impls, neither of which apply here
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{DepartmentHeadcount, DuplicatedEmployee, NameLengthStatistics, RosterStatistics};
+    use crate::employee_store::DepartmentInfo;
+
+    fn department(name: &str, employees: Vec<&str>) -> DepartmentInfo {
+        DepartmentInfo {
+            department: name.to_string(),
+            employee_names: employees.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    fn test_departments() -> Vec<DepartmentInfo> {
+        vec![
+            department("Pie Quality Control", vec!["Bob Bobertson", "Weebl Bull"]),
+            department("Pie Rejection", vec![]),
+            department("Stealthy Buccaneering", vec!["Chris the Ninja Pirate", "Weebl Bull"]),
+        ]
+    }
+
+    fn strings(values: Vec<&str>) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_statistics_for_populated_roster() {
+        let expected = RosterStatistics {
+            departments: vec![
+                DepartmentHeadcount { department: "Pie Quality Control".to_string(), headcount: 2 },
+                DepartmentHeadcount { department: "Pie Rejection".to_string(), headcount: 0 },
+                DepartmentHeadcount { department: "Stealthy Buccaneering".to_string(), headcount: 2 },
+            ],
+            total_employees: 4,
+            distinct_employees: 3,
+            largest_departments: strings(vec!["Pie Quality Control", "Stealthy Buccaneering"]),
+            smallest_departments: strings(vec!["Pie Quality Control", "Stealthy Buccaneering"]),
+            empty_departments: strings(vec!["Pie Rejection"]),
+            employees_in_multiple_departments: vec![DuplicatedEmployee {
+                employee_name: "Weebl Bull".to_string(),
+                departments: strings(vec!["Pie Quality Control", "Stealthy Buccaneering"]),
+            }],
+            name_lengths: Some(NameLengthStatistics { shortest: 10, longest: 22, mean: 15.0 }),
+        };
+        assert_eq!(RosterStatistics::from_departments(&test_departments()), expected);
+    }
+
+    #[test]
+    fn test_statistics_for_empty_roster() {
+        let statistics = RosterStatistics::from_departments(&[]);
+        assert_eq!(statistics.total_employees, 0);
+        assert_eq!(statistics.largest_departments, Vec::<String>::new());
+        assert_eq!(statistics.name_lengths, None);
+    }
+
+    #[test]
+    fn test_render_text_aligns_table() {
+        let lines = RosterStatistics::from_departments(&test_departments()).render_text();
+        assert_eq!(lines, strings(vec![
+            "Department            | Headcount",
+            "----------------------+----------",
+            "Pie Quality Control   |         2",
+            "Pie Rejection         |         0",
+            "Stealthy Buccaneering |         2",
+            "Total employees: 4 (3 distinct)",
+            "Largest department(s): Pie Quality Control, Stealthy Buccaneering (2)",
+            "Smallest department(s): Pie Quality Control, Stealthy Buccaneering (2)",
+            "Empty department(s): Pie Rejection",
+            "Employees in more than one department: Weebl Bull (Pie Quality Control, Stealthy Buccaneering)",
+            "Name length (characters): shortest 10, longest 22, mean 15.0",
+        ]));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json: serde_json::Value = serde_json::from_str(
+            &RosterStatistics::from_departments(&test_departments()).render_json()
+        ).unwrap();
+        assert_eq!(json["total_employees"], 4);
+        assert_eq!(json["empty_departments"][0], "Pie Rejection");
+        assert_eq!(json["departments"][2]["department"], "Stealthy Buccaneering");
+        assert_eq!(json["name_lengths"]["longest"], 22);
+    }
+}
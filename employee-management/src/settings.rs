@@ -0,0 +1,353 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use log::Level;
+use serde::Deserialize;
+
+use crate::access::DEFAULT_USERS_FILE;
+use crate::command::DEFAULT_ALIAS_FILE;
+use crate::hooks::DEFAULT_HOOKS_FILE;
+use crate::locale::{LANGUAGES, DEFAULT_LANGUAGE};
+use crate::snapshot::DEFAULT_SNAPSHOT_DIRECTORY;
+
+pub const DEFAULT_CONFIG_FILE: &'static str = "employee-management.toml";
+
+pub const USAGE_TEXT: &'static str = "Usage: employee-management [options]
+
+Options (each overrides the same setting in the config file):
+  --config <path>          TOML config file (default \"employee-management.toml\", if present)
+  --store <memory|json>    Store backend (default memory)
+  --store-path <path>      File for the json store backend
+  --log-level <level>      One of error, warn, info, debug, trace (default info)
+  --output <text|json>     Format for command results (default text)
+  --language <en|fr>       Language for commands and messages (default en)
+  --prompt <text>          Prompt shown before each command (default depends on the language)
+  --history-file <path>    File to append each entered command to
+  --aliases-file <path>    Alias definitions (default \"aliases.conf\")
+  --users-file <path>      Users and roles (default \"users.conf\") - must exist unless access control is disabled
+  --disable-access-control Allow every command without logging in, instead of reading the users file
+  --hooks-file <path>      Shell commands to run on roster changes (default \"hooks.conf\")
+  --snapshot-directory <path>  Where roster snapshots are kept (default \"snapshots\")
+  --dry-run                Report what commands would change without changing anything
+  --help                   Show this help";
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum StoreBackend {
+    Memory,
+    JsonFile,
+}
+
+impl StoreBackend {
+    pub fn from_name(name: &str) -> Option<StoreBackend> {
+        match name {
+            "memory" => Some(StoreBackend::Memory),
+            "json" => Some(StoreBackend::JsonFile),
+            _ => None
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn from_name(name: &str) -> Option<OutputFormat> {
+        match name {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            _ => None
+        }
+    }
+}
+
+// Every setting is optional at this stage, so that the config file and command line can each give any of them -
+//  names in the config file match the long CLI options, with '_' for '-' (e.g. "store_path").
+#[derive(Eq, PartialEq, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartialSettings {
+    pub store: Option<String>,
+    pub store_path: Option<PathBuf>,
+    pub log_level: Option<String>,
+    pub output: Option<String>,
+    pub language: Option<String>,
+    pub prompt: Option<String>,
+    pub history_file: Option<PathBuf>,
+    pub aliases_file: Option<PathBuf>,
+    pub users_file: Option<PathBuf>,
+    pub disable_access_control: Option<bool>,
+    pub hooks_file: Option<PathBuf>,
+    pub snapshot_directory: Option<PathBuf>,
+    pub dry_run: Option<bool>,
+}
+
+impl PartialSettings {
+    // Anything given here wins over the same setting in the other
+    fn overriding(self, other: PartialSettings) -> PartialSettings {
+        PartialSettings {
+            store: self.store.or(other.store),
+            store_path: self.store_path.or(other.store_path),
+            log_level: self.log_level.or(other.log_level),
+            output: self.output.or(other.output),
+            language: self.language.or(other.language),
+            prompt: self.prompt.or(other.prompt),
+            history_file: self.history_file.or(other.history_file),
+            aliases_file: self.aliases_file.or(other.aliases_file),
+            users_file: self.users_file.or(other.users_file),
+            disable_access_control: self.disable_access_control.or(other.disable_access_control),
+            hooks_file: self.hooks_file.or(other.hooks_file),
+            snapshot_directory: self.snapshot_directory.or(other.snapshot_directory),
+            dry_run: self.dry_run.or(other.dry_run),
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Default)]
+pub struct CommandLine {
+    pub show_help: bool,
+    pub config_file: Option<PathBuf>,
+    pub settings: PartialSettings,
+}
+
+fn option_value(name: &str, inline_value: Option<&str>, remaining: &mut dyn Iterator<Item = &String>)
+    -> Result<String, String>
+{
+    match inline_value {
+        Some(value) => Ok(value.to_string()),
+        None => remaining.next().cloned().ok_or(format!("Option \"--{}\" requires a value", name))
+    }
+}
+
+// Takes the arguments after the program name. Options can be given as "--name value" or "--name=value".
+pub fn parse_command_line(args: &[String]) -> Result<CommandLine, String> {
+    let mut command_line = CommandLine::default();
+    let settings = &mut command_line.settings;
+    let mut remaining = args.iter();
+    while let Some(arg) = remaining.next() {
+        let option = match arg.strip_prefix("--") {
+            Some(option) => option,
+            None => return Err(format!("Unexpected argument \"{}\"", arg))
+        };
+        let (name, inline_value) = match option.find('=') {
+            Some(position) => (&option[..position], Some(&option[position + 1..])),
+            None => (option, None)
+        };
+        let mut value = || option_value(name, inline_value, &mut remaining);
+        match name {
+            "help" if inline_value.is_none() => command_line.show_help = true,
+            "dry-run" if inline_value.is_none() => settings.dry_run = Some(true),
+            "disable-access-control" if inline_value.is_none() => settings.disable_access_control = Some(true),
+            "config" => command_line.config_file = Some(PathBuf::from(value()?)),
+            "store" => settings.store = Some(value()?),
+            "store-path" => settings.store_path = Some(PathBuf::from(value()?)),
+            "log-level" => settings.log_level = Some(value()?),
+            "output" => settings.output = Some(value()?),
+            "language" => settings.language = Some(value()?),
+            "prompt" => settings.prompt = Some(value()?),
+            "history-file" => settings.history_file = Some(PathBuf::from(value()?)),
+            "aliases-file" => settings.aliases_file = Some(PathBuf::from(value()?)),
+            "users-file" => settings.users_file = Some(PathBuf::from(value()?)),
+            "hooks-file" => settings.hooks_file = Some(PathBuf::from(value()?)),
+            "snapshot-directory" => settings.snapshot_directory = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("Unknown option \"{}\" - use --help to list options", arg))
+        }
+    }
+    Ok(command_line)
+}
+
+// The default config file is optional, but one named on the command line must exist
+pub fn read_config_file(file_path: &Path, must_exist: bool) -> Result<PartialSettings, String> {
+    match fs::read_to_string(file_path) {
+        Ok(text) => toml::from_str(&text)
+            .map_err(|e| format!("Could not parse config file \"{}\" - {}", file_path.display(), e)),
+        Err(e) if e.kind() == ErrorKind::NotFound && !must_exist => Ok(PartialSettings::default()),
+        Err(e) => Err(format!("Could not read config file \"{}\" - {}", file_path.display(), e))
+    }
+}
+
+#[derive(Eq, PartialEq, Debug)]
+pub struct Settings {
+    pub store_backend: StoreBackend,
+    pub store_path: Option<PathBuf>,
+    pub log_level: Level,
+    pub output_format: OutputFormat,
+    pub language: String,
+    // When not set, the prompt comes from the language's message catalog
+    pub prompt: Option<String>,
+    pub history_file: Option<PathBuf>,
+    pub aliases_file: PathBuf,
+    pub users_file: PathBuf,
+    pub disable_access_control: bool,
+    pub hooks_file: PathBuf,
+    pub snapshot_directory: PathBuf,
+    pub dry_run: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings::resolve(PartialSettings::default()).unwrap()
+    }
+}
+
+impl Settings {
+    // Fills in defaults for anything not given, and checks the values are ones we understand
+    pub fn resolve(settings: PartialSettings) -> Result<Settings, String> {
+        let store_backend = match settings.store {
+            Some(name) => StoreBackend::from_name(&name)
+                .ok_or(format!("Unknown store backend \"{}\" - expected memory or json", name))?,
+            None => StoreBackend::Memory
+        };
+        if store_backend == StoreBackend::JsonFile && settings.store_path.is_none() {
+            return Err("The json store backend requires a store path".to_string());
+        }
+        let log_level = match settings.log_level {
+            Some(name) => name.parse::<Level>()
+                .map_err(|_| format!("Unknown log level \"{}\" - expected error, warn, info, debug or trace", name))?,
+            None => Level::Info
+        };
+        let output_format = match settings.output {
+            Some(name) => OutputFormat::from_name(&name)
+                .ok_or(format!("Unknown output format \"{}\" - expected text or json", name))?,
+            None => OutputFormat::Text
+        };
+        let language = settings.language.unwrap_or(DEFAULT_LANGUAGE.to_string());
+        if !LANGUAGES.contains(&language.as_str()) {
+            return Err(format!("Unknown language \"{}\" - expected one of {}", language, LANGUAGES.join(", ")));
+        }
+        Ok(Settings {
+            store_backend,
+            store_path: settings.store_path,
+            log_level,
+            output_format,
+            language,
+            prompt: settings.prompt,
+            history_file: settings.history_file,
+            aliases_file: settings.aliases_file.unwrap_or(PathBuf::from(DEFAULT_ALIAS_FILE)),
+            users_file: settings.users_file.unwrap_or(PathBuf::from(DEFAULT_USERS_FILE)),
+            disable_access_control: settings.disable_access_control.unwrap_or(false),
+            hooks_file: settings.hooks_file.unwrap_or(PathBuf::from(DEFAULT_HOOKS_FILE)),
+            snapshot_directory: settings.snapshot_directory.unwrap_or(PathBuf::from(DEFAULT_SNAPSHOT_DIRECTORY)),
+            dry_run: settings.dry_run.unwrap_or(false),
+        })
+    }
+
+    // Command line settings win over the config file, which wins over the defaults
+    pub fn load(config_file: Option<PathBuf>, command_line_settings: PartialSettings) -> Result<Settings, String> {
+        let config_settings = match config_file {
+            Some(file_path) => read_config_file(&file_path, true)?,
+            None => read_config_file(Path::new(DEFAULT_CONFIG_FILE), false)?
+        };
+        Settings::resolve(command_line_settings.overriding(config_settings))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_command_line, read_config_file, CommandLine, OutputFormat, PartialSettings, Settings, StoreBackend,
+    };
+    use log::Level;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn args(values: Vec<&str>) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_command_line_options() {
+        let parsed = parse_command_line(&args(vec![
+            "--config", "team.toml", "--log-level=debug", "--prompt", "> ", "--dry-run",
+            "--snapshot-directory", "/var/lib/roster-snapshots", "--disable-access-control",
+        ]));
+        assert_eq!(parsed, Ok(CommandLine {
+            show_help: false,
+            config_file: Some(PathBuf::from("team.toml")),
+            settings: PartialSettings {
+                log_level: Some("debug".to_string()),
+                prompt: Some("> ".to_string()),
+                dry_run: Some(true),
+                snapshot_directory: Some(PathBuf::from("/var/lib/roster-snapshots")),
+                disable_access_control: Some(true),
+                ..PartialSettings::default()
+            },
+        }));
+        assert!(parse_command_line(&args(vec!["--dry-run", "--help"])).unwrap().show_help);
+    }
+
+    #[test]
+    fn test_parse_command_line_errors() {
+        assert_eq!(
+            parse_command_line(&args(vec!["--store"])),
+            Err("Option \"--store\" requires a value".to_string())
+        );
+        assert_eq!(
+            parse_command_line(&args(vec!["--verbose"])),
+            Err("Unknown option \"--verbose\" - use --help to list options".to_string())
+        );
+        assert_eq!(
+            parse_command_line(&args(vec!["roster.json"])),
+            Err("Unexpected argument \"roster.json\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_defaults() {
+        let settings = Settings::default();
+        assert_eq!(settings.store_backend, StoreBackend::Memory);
+        assert_eq!(settings.log_level, Level::Info);
+        assert_eq!(settings.output_format, OutputFormat::Text);
+        assert_eq!(settings.language, "en");
+        assert_eq!(settings.prompt, None);
+        assert_eq!(settings.aliases_file, PathBuf::from("aliases.conf"));
+        assert_eq!(settings.snapshot_directory, PathBuf::from("snapshots"));
+        assert!(!settings.dry_run);
+        assert!(!settings.disable_access_control);
+    }
+
+    #[test]
+    fn test_command_line_overrides_config_file_which_overrides_defaults() {
+        let path = env::temp_dir().join(format!("employee-management-settings-{}.toml", std::process::id()));
+        let config = "store = \"json\"\nstore_path = \"roster.json\"\nlog_level = \"warn\"\noutput = \"json\"\n";
+        fs::write(&path, config).unwrap();
+        let command_line = PartialSettings { log_level: Some("trace".to_string()), ..PartialSettings::default() };
+        let settings = Settings::load(Some(path.clone()), command_line);
+        fs::remove_file(&path).unwrap();
+
+        let settings = settings.unwrap();
+        assert_eq!(settings.store_backend, StoreBackend::JsonFile);
+        assert_eq!(settings.store_path, Some(PathBuf::from("roster.json")));
+        assert_eq!(settings.log_level, Level::Trace);
+        assert_eq!(settings.output_format, OutputFormat::Json);
+        assert_eq!(settings.prompt, None);
+    }
+
+    #[test]
+    fn test_config_file_errors() {
+        let path = env::temp_dir().join(format!("employee-management-bad-settings-{}.toml", std::process::id()));
+        fs::write(&path, "colour = \"blue\"\n").unwrap();
+        let unknown_setting = read_config_file(&path, true);
+        fs::remove_file(&path).unwrap();
+
+        assert!(unknown_setting.unwrap_err().starts_with("Could not parse config file"));
+        assert_eq!(read_config_file(&path, false), Ok(PartialSettings::default()));
+        assert!(read_config_file(&path, true).unwrap_err().starts_with("Could not read config file"));
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_values() {
+        assert_eq!(
+            Settings::resolve(PartialSettings { store: Some("json".to_string()), ..PartialSettings::default() }),
+            Err("The json store backend requires a store path".to_string())
+        );
+        assert_eq!(
+            Settings::resolve(PartialSettings { output: Some("yaml".to_string()), ..PartialSettings::default() }),
+            Err("Unknown output format \"yaml\" - expected text or json".to_string())
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::employee_store::DepartmentInfo;
+
+pub const DEFAULT_SNAPSHOT_DIRECTORY: &'static str = "snapshots";
+// Can be given in place of a label when diffing, to compare against the roster as it is now
+pub const CURRENT_ROSTER_LABEL: &'static str = "current";
+
+const LABEL_PATTERN: &'static str = r"^[A-Za-z0-9_.-]+$";
+
+thread_local! {
+    static SNAPSHOT_DIRECTORY: RefCell<PathBuf> = RefCell::new(PathBuf::from(DEFAULT_SNAPSHOT_DIRECTORY));
+}
+
+// Chosen once at start-up from the settings, as the handlers that use it are plain functions
+pub fn set_snapshot_directory(directory: &Path) {
+    SNAPSHOT_DIRECTORY.with(|current| *current.borrow_mut() = directory.to_path_buf());
+}
+
+pub fn snapshot_directory() -> PathBuf {
+    SNAPSHOT_DIRECTORY.with(|current| current.borrow().clone())
+}
+
+pub fn validate_label(label: &str) -> Result<(), String> {
+    // Labels become file names, so are restricted to characters that can't escape the snapshot directory
+    if !Regex::new(LABEL_PATTERN).unwrap().is_match(label) || label.starts_with('.') {
+        return Err(format!(
+            "Snapshot label \"{}\" must be a single word of letters, digits, '-', '_' or '.' (not starting with '.')",
+            label
+        ));
+    }
+    if label == CURRENT_ROSTER_LABEL {
+        return Err(format!("Snapshot label \"{}\" is reserved for the current roster", label));
+    }
+    Ok(())
+}
+
+fn snapshot_path(directory: &Path, label: &str) -> PathBuf {
+    directory.join(format!("{}.json", label))
+}
+
+// Gives the path the snapshot would be saved to, as snapshots are never overwritten
+pub fn check_snapshot_can_be_saved(directory: &Path, label: &str) -> Result<PathBuf, String> {
+    validate_label(label)?;
+    let path = snapshot_path(directory, label);
+    if path.exists() {
+        return Err(format!("Snapshot \"{}\" already exists", label));
+    }
+    Ok(path)
+}
+
+pub fn save_snapshot(directory: &Path, label: &str, departments: &[DepartmentInfo]) -> Result<PathBuf, String> {
+    let path = check_snapshot_can_be_saved(directory, label)?;
+    fs::create_dir_all(directory)
+        .map_err(|e| format!("Could not create snapshot directory \"{}\" - {}", directory.display(), e))?;
+    let json = serde_json::to_string_pretty(departments).unwrap();
+    fs::write(&path, json)
+        .map_err(|e| format!("Could not write snapshot \"{}\" - {}", path.display(), e))?;
+    Ok(path)
+}
+
+pub fn load_snapshot(directory: &Path, label: &str) -> Result<Vec<DepartmentInfo>, String> {
+    validate_label(label)?;
+    let path = snapshot_path(directory, label);
+    if !path.exists() {
+        return Err(format!("Snapshot \"{}\" does not exist", label));
+    }
+    let json = fs::read_to_string(&path)
+        .map_err(|e| format!("Could not read snapshot \"{}\" - {}", path.display(), e))?;
+    let departments: Vec<DepartmentInfo> = serde_json::from_str(&json)
+        .map_err(|e| format!("Could not parse snapshot \"{}\" - {}", path.display(), e))?;
+    check_departments_are_unique(label, &departments)?;
+    Ok(departments)
+}
+
+// A hand-edited snapshot could list a department twice, which a restore could only half apply
+fn check_departments_are_unique(label: &str, departments: &[DepartmentInfo]) -> Result<(), String> {
+    let mut seen = BTreeSet::new();
+    for info in departments {
+        if !seen.insert(&info.department) {
+            return Err(format!("Snapshot \"{}\" lists department \"{}\" more than once", label, info.department));
+        }
+    }
+    Ok(())
+}
+
+#[derive(PartialEq, Debug)]
+pub struct RosterDiff {
+    pub added_departments: Vec<String>,
+    pub removed_departments: Vec<String>,
+    // Employees are given as (department, employee name), and include those in added or removed departments
+    pub added_employees: Vec<(String, String)>,
+    pub removed_employees: Vec<(String, String)>,
+}
+
+fn department_set(departments: &[DepartmentInfo]) -> BTreeSet<String> {
+    departments.iter().map(|info| info.department.clone()).collect()
+}
+
+fn employee_set(departments: &[DepartmentInfo]) -> BTreeSet<(String, String)> {
+    departments
+        .iter()
+        .flat_map(|info| info.employee_names.iter().map(move |name| (info.department.clone(), name.clone())))
+        .collect()
+}
+
+impl RosterDiff {
+
+    pub fn between(before: &[DepartmentInfo], after: &[DepartmentInfo]) -> RosterDiff {
+        let (departments_before, departments_after) = (department_set(before), department_set(after));
+        let (employees_before, employees_after) = (employee_set(before), employee_set(after));
+        RosterDiff {
+            added_departments: departments_after.difference(&departments_before).cloned().collect(),
+            removed_departments: departments_before.difference(&departments_after).cloned().collect(),
+            added_employees: employees_after.difference(&employees_before).cloned().collect(),
+            removed_employees: employees_before.difference(&employees_after).cloned().collect(),
+        }
+    }
+
+    pub fn change_count(&self) -> usize {
+        self.added_departments.len() + self.removed_departments.len()
+            + self.added_employees.len() + self.removed_employees.len()
+    }
+
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = vec![];
+        lines.extend(self.added_departments.iter().map(|d| format!("+ department \"{}\"", d)));
+        lines.extend(self.removed_departments.iter().map(|d| format!("- department \"{}\"", d)));
+        lines.extend(self.added_employees.iter().map(|(d, e)| format!("+ employee \"{}\" in \"{}\"", e, d)));
+        lines.extend(self.removed_employees.iter().map(|(d, e)| format!("- employee \"{}\" in \"{}\"", e, d)));
+        lines
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{load_snapshot, save_snapshot, set_snapshot_directory, snapshot_directory, validate_label, RosterDiff};
+    use crate::employee_store::DepartmentInfo;
+    use std::env;
+    use std::fs;
+    use std::path::Path;
+
+    fn department(name: &str, employees: Vec<&str>) -> DepartmentInfo {
+        DepartmentInfo {
+            department: name.to_string(),
+            employee_names: employees.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    fn before() -> Vec<DepartmentInfo> {
+        vec![
+            department("Pie Quality Control", vec!["Bob Bobertson", "Weebl Bull"]),
+            department("Pie Rejection", vec!["Hairy Lee"]),
+        ]
+    }
+
+    fn after() -> Vec<DepartmentInfo> {
+        vec![
+            department("Pie Quality Control", vec!["Weebl Bull"]),
+            department("Stealthy Buccaneering", vec!["Bob Bobertson"]),
+        ]
+    }
+
+    fn pair(department: &str, employee: &str) -> (String, String) {
+        (department.to_string(), employee.to_string())
+    }
+
+    #[test]
+    fn test_validate_label() {
+        assert_eq!(validate_label("before-reorg_2"), Ok(()));
+        assert!(validate_label("../escape").is_err());
+        assert!(validate_label(".hidden").is_err());
+        assert_eq!(
+            validate_label("current"),
+            Err("Snapshot label \"current\" is reserved for the current roster".to_string())
+        );
+    }
+
+    #[test]
+    fn test_diff_between_rosters() {
+        assert_eq!(
+            RosterDiff::between(&before(), &after()),
+            RosterDiff {
+                added_departments: vec!["Stealthy Buccaneering".to_string()],
+                removed_departments: vec!["Pie Rejection".to_string()],
+                added_employees: vec![pair("Stealthy Buccaneering", "Bob Bobertson")],
+                removed_employees: vec![pair("Pie Quality Control", "Bob Bobertson"), pair("Pie Rejection", "Hairy Lee")],
+            }
+        );
+    }
+
+    #[test]
+    fn test_describe_diff() {
+        let diff = RosterDiff::between(&before(), &after());
+        assert_eq!(diff.change_count(), 5);
+        assert_eq!(diff.describe(), vec![
+            "+ department \"Stealthy Buccaneering\"",
+            "- department \"Pie Rejection\"",
+            "+ employee \"Bob Bobertson\" in \"Stealthy Buccaneering\"",
+            "- employee \"Bob Bobertson\" in \"Pie Quality Control\"",
+            "- employee \"Hairy Lee\" in \"Pie Rejection\"",
+        ]);
+    }
+
+    #[test]
+    fn test_diff_of_identical_rosters_is_empty() {
+        assert_eq!(RosterDiff::between(&before(), &before()).change_count(), 0);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_and_refuses_overwrite() {
+        let directory = env::temp_dir().join(format!("employee-management-snapshots-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+
+        save_snapshot(&directory, "before", &before()).unwrap();
+        let loaded = load_snapshot(&directory, "before");
+        let second_save = save_snapshot(&directory, "before", &after());
+        let missing = load_snapshot(&directory, "after");
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(loaded, Ok(before()));
+        assert_eq!(second_save, Err("Snapshot \"before\" already exists".to_string()));
+        assert_eq!(missing, Err("Snapshot \"after\" does not exist".to_string()));
+    }
+
+    #[test]
+    fn test_load_rejects_duplicate_departments() {
+        let directory = env::temp_dir().join(format!("employee-management-duplicate-snapshot-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let mut departments = before();
+        departments.push(department("Pie Rejection", vec!["Bob Bobertson"]));
+
+        save_snapshot(&directory, "doubled", &departments).unwrap();
+        let loaded = load_snapshot(&directory, "doubled");
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            loaded,
+            Err("Snapshot \"doubled\" lists department \"Pie Rejection\" more than once".to_string())
+        );
+    }
+
+    #[test]
+    fn test_set_snapshot_directory() {
+        assert_eq!(snapshot_directory(), Path::new("snapshots"));
+        set_snapshot_directory(Path::new("/var/lib/roster-snapshots"));
+        assert_eq!(snapshot_directory(), Path::new("/var/lib/roster-snapshots"));
+    }
+}
@@ -0,0 +1,28 @@
+// Shared by the benchmarks, so they all search the same kind of text
+
+const WORDS: [&str; 12] = [
+    "nobody", "frog", "bog", "livelong", "dreary", "public", "banish", "pair", "admiring", "somebody", "tell", "day",
+];
+const WORDS_PER_LINE: usize = 10;
+
+// Ends log-like lines with words picked by a simple LCG, so that every run sees the same text without needing extra
+//  crates
+pub struct WordGenerator {
+    seed: u64,
+}
+
+impl WordGenerator {
+    pub fn new() -> WordGenerator {
+        WordGenerator { seed: 42 }
+    }
+
+    // Appends a line's words and its line ending
+    pub fn finish_line(&mut self, contents: &mut String) {
+        for _ in 0..WORDS_PER_LINE {
+            self.seed = self.seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            contents.push(' ');
+            contents.push_str(WORDS[(self.seed >> 33) as usize % WORDS.len()]);
+        }
+        contents.push('\n');
+    }
+}
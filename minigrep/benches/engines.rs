@@ -0,0 +1,71 @@
+mod common;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use minigrep::{AhoCorasick, Horspool};
+
+use common::WordGenerator;
+
+const LINES: usize = 200_000;
+const CODES: [&str; 4] = ["E1042", "E2001", "E3107", "E4400"];
+
+// A log-like buffer held in memory, so only the engines are measured - one line in 97 carries an error code
+fn create_corpus() -> String {
+    let mut contents = String::new();
+    let mut words = WordGenerator::new();
+    for line_number in 0..LINES {
+        if line_number % 97 == 0 {
+            contents.push_str(&format!("ERROR {} line {}:", CODES[line_number % CODES.len()], line_number));
+        } else {
+            contents.push_str(&format!("INFO line {}:", line_number));
+        }
+        words.finish_line(&mut contents);
+    }
+    contents
+}
+
+// Counts the lines holding a hit, moving on to the next line after each one as the searcher does
+fn count_lines_with_hits(haystack: &[u8], find: impl Fn(&[u8]) -> Option<usize>) -> usize {
+    let mut count = 0;
+    let mut position = 0;
+    while let Some(hit) = find(&haystack[position..]) {
+        count += 1;
+        position += hit;
+        position = haystack[position..].iter().position(|&byte| byte == b'\n').map_or(haystack.len(), |newline| {
+            position + newline + 1
+        });
+    }
+    count
+}
+
+fn bench_single_pattern(c: &mut Criterion) {
+    let corpus = create_corpus();
+    let mut group = c.benchmark_group("single_pattern");
+    group.throughput(Throughput::Bytes(corpus.len() as u64));
+
+    group.bench_function("str_contains_per_line", |b| {
+        b.iter(|| corpus.lines().filter(|line| line.contains("E2001")).count())
+    });
+    let engine = Horspool::new(b"E2001");
+    group.bench_function("horspool_whole_buffer", |b| {
+        b.iter(|| count_lines_with_hits(corpus.as_bytes(), |haystack| engine.find(haystack)))
+    });
+    group.finish();
+}
+
+fn bench_many_patterns(c: &mut Criterion) {
+    let corpus = create_corpus();
+    let mut group = c.benchmark_group("many_patterns");
+    group.throughput(Throughput::Bytes(corpus.len() as u64));
+
+    group.bench_function("str_contains_each_pattern_per_line", |b| {
+        b.iter(|| corpus.lines().filter(|line| CODES.iter().any(|code| line.contains(code))).count())
+    });
+    let engine = AhoCorasick::new(&CODES);
+    group.bench_function("aho_corasick_whole_buffer", |b| {
+        b.iter(|| count_lines_with_hits(corpus.as_bytes(), |haystack| engine.find(haystack).map(|(_, hit)| hit.start)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_single_pattern, bench_many_patterns);
+criterion_main!(benches);
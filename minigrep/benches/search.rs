@@ -0,0 +1,58 @@
+mod common;
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use minigrep::Config;
+
+use common::WordGenerator;
+
+const FILES: usize = 64;
+const LINES_PER_FILE: usize = 20_000;
+
+// A generated corpus of log-like files, the same every run, with the ERROR lines being searched for spread evenly
+//  across the files
+fn create_corpus() -> PathBuf {
+    let root = env::temp_dir().join(format!("minigrep-bench-corpus-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    let mut words = WordGenerator::new();
+    for file_number in 0..FILES {
+        let mut contents = String::new();
+        for line_number in 0..LINES_PER_FILE {
+            let level = if line_number % 97 == 0 { "ERROR" } else { "INFO" };
+            contents.push_str(&format!("{} line {}:", level, line_number));
+            words.finish_line(&mut contents);
+        }
+        fs::write(root.join(format!("log-{:02}.txt", file_number)), contents).unwrap();
+    }
+    root
+}
+
+fn bench_threads(c: &mut Criterion) {
+    let root = create_corpus();
+    let mut group = c.benchmark_group("multi_file_search");
+    group.sample_size(10);
+    let default_threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    // Benchmark IDs must be unique, and the default may be one of the fixed counts
+    let mut thread_counts = vec![1, 2, 4, default_threads];
+    thread_counts.sort();
+    thread_counts.dedup();
+    for threads in thread_counts {
+        let config = Config {
+            threads: Some(threads),
+            ..Config::new("ERROR".to_string(), vec![root.to_str().unwrap().to_string()])
+        };
+        group.bench_with_input(BenchmarkId::new("threads", threads), &config, |b, config| {
+            b.iter(|| minigrep::search_to(config, false, &mut io::sink()).unwrap())
+        });
+    }
+    group.finish();
+    fs::remove_dir_all(&root).unwrap();
+}
+
+criterion_group!(benches, bench_threads);
+criterion_main!(benches);
@@ -1,8 +1,50 @@
 use std::env;
+use std::fs;
 use mockall_derive::automock;
 
 type EnvVarResult = Result<String, env::VarError>;
 
+// The path that stands for standard input, as in most command line tools
+pub const STDIN_PATH: &str = "-";
+
+pub const USAGE: &str = "\
+Usage: minigrep [OPTIONS] QUERY [PATH...]
+       minigrep [OPTIONS] -e QUERY... | -f FILE... [PATH...]
+
+Searches each file for lines containing QUERY, walking directories recursively. With no PATH, or a PATH of \"-\",
+standard input is searched. Binary files (with a NUL byte near the start) are skipped, counting as files without a
+matching line.
+
+Options:
+  -e, --regexp QUERY        Search for QUERY - give more than once to search for lines matching any of them
+  -f, --file FILE           Search for every query in FILE, one per line
+      --all-match           Only select lines that match every query, rather than any of them
+  -i, --ignore-case         Match case-insensitively (also set by the CASE_INSENSITIVE environment variable)
+      --no-ignore-case      Match case-sensitively, even if CASE_INSENSITIVE is set
+  -E, --regex               Treat QUERY as a regular expression
+  -w, --word-regexp         Only match whole words, with no letter, digit or \"_\" either side
+  -x, --line-regexp         Only match whole lines
+  -v, --invert-match        Print lines that don't match
+  -n, --line-number         Prefix each line with its line number
+  -b, --byte-offset         Prefix each line with the byte offset of its start
+  -A, --after-context N     Print N lines of context after each matching line
+  -B, --before-context N    Print N lines of context before each matching line
+  -C, --context N           Print N lines of context before and after each matching line
+  -c, --count               Print the number of matching lines in each file
+  -l, --files-with-matches  Print only the names of files with matching lines
+  -L, --files-without-match Print only the names of files without matching lines
+  -m, --max-count N         Stop searching each file after N matching lines
+  -q, --quiet               Print nothing, only exiting with a status - stops at the first matching line
+      --threads N           Search files on N threads (by default, one per CPU core). Results are held back to print
+                            in order, up to 16 MiB in all - past that, files are searched again as their turn comes
+      --color[=WHEN]        Highlight matches - WHEN is always, never or auto (the default)
+  -h, --help                Print this help
+  -V, --version             Print the version
+
+Options can be combined (e.g. -inC2), and \"--\" ends the options so that the query can start with \"-\".
+
+The exit status is 0 if a line was selected (or with -L, a file listed), 1 if not, and 2 if there was an error.";
+
 #[automock]
 trait VariableAccess {
     fn get_var(&self, key: &'static str) -> EnvVarResult;
@@ -16,48 +58,257 @@ impl VariableAccess for EnvironmentVariableAccessor {
     }
 }
 
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum ColorChoice {
+    Always,
+    Never,
+    // Only when writing to a terminal
+    Auto,
+}
+
+impl ColorChoice {
+    fn from_name(name: &str) -> Result<ColorChoice, String> {
+        match name {
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            "auto" => Ok(ColorChoice::Auto),
+            _ => Err(format!("Invalid colour choice \"{}\" - expected always, never or auto", name))
+        }
+    }
+}
+
+fn parse_number(option: &str, value: &str) -> Result<usize, String> {
+    value.parse().map_err(|_| format!("Option \"{}\" needs a whole number, not \"{}\"", option, value))
+}
+
+// One query per line, as in grep - so an empty file has no queries and matches nothing
+fn read_pattern_file(path: &str) -> Result<Vec<String>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Couldn't read pattern file \"{}\": {}", path, e))?;
+    Ok(contents.lines().map(|line| line.to_string()).collect())
+}
+
 #[derive(Eq, PartialEq, Debug)]
 pub struct Config {
-    pub query: String,
-    pub filename: String,
-    pub case_sensitive: bool
+    // A line is selected if it matches any of these, or every one of them with `match_all`
+    pub patterns: Vec<String>,
+    pub match_all: bool,
+    pub paths: Vec<String>,
+    pub case_sensitive: bool,
+    pub regex: bool,
+    pub whole_words: bool,
+    pub whole_lines: bool,
+    pub invert_match: bool,
+    pub line_numbers: bool,
+    pub byte_offset: bool,
+    // Lines to show after and before each matching line
+    pub after_context: usize,
+    pub before_context: usize,
+    pub count: bool,
+    pub files_with_matches: bool,
+    pub files_without_match: bool,
+    // Matching lines to select from each file before moving on to the next, if limited
+    pub max_count: Option<usize>,
+    pub quiet: bool,
+    // None for one thread per core
+    pub threads: Option<usize>,
+    pub color: ColorChoice
 }
 
 impl Config {
+    // A plain case-sensitive search for a single query, with every option turned off
+    pub fn new(query: String, paths: Vec<String>) -> Config {
+        Config {
+            patterns: vec![query],
+            match_all: false,
+            paths,
+            case_sensitive: true,
+            regex: false,
+            whole_words: false,
+            whole_lines: false,
+            invert_match: false,
+            line_numbers: false,
+            byte_offset: false,
+            after_context: 0,
+            before_context: 0,
+            count: false,
+            files_with_matches: false,
+            files_without_match: false,
+            max_count: None,
+            quiet: false,
+            threads: None,
+            color: ColorChoice::Auto
+        }
+    }
+}
+
+// What the command line asks for - usually a search, but --help and --version win over everything else
+#[derive(Eq, PartialEq, Debug)]
+pub enum Invocation {
+    Search(Config),
+    Help,
+    Version
+}
+
+impl Invocation {
 
     fn new_specifying_var_source<I: Iterator<Item = String>, V: VariableAccess>(mut args: I, env_var_source: V)
-                                                                                 -> Result<Config, &'static str>
+                                                                                 -> Result<Invocation, String>
     {
         let _program_name = args.next();
 
-        let query = match args.next() {
-            Some(arg) => arg,
-            None => return Err("Didn't get a query string")
-        };
+        let mut config = Config::new(String::new(), vec![]);
+        // Queries given with -e or -f - if there are none, the first positional argument is the query
+        let mut patterns: Option<Vec<String>> = None;
+        // Flags override the environment variable, so it is only consulted if neither was given
+        let mut ignore_case: Option<bool> = None;
+        let mut positional = vec![];
+        let mut options_ended = false;
+
+        while let Some(arg) = args.next() {
+            if options_ended || arg == "-" || !arg.starts_with('-') {
+                positional.push(arg);
+            } else if arg == "--" {
+                options_ended = true;
+            } else if let Some(long_option) = arg.strip_prefix("--") {
+                let (name, value) = match long_option.find('=') {
+                    Some(index) => (&long_option[..index], Some(long_option[index + 1..].to_string())),
+                    None => (long_option, None)
+                };
+                let option = format!("--{}", name);
+                // Options that need a value take it from after the "=" or, failing that, the next argument
+                let mut required_value = || value.clone().or_else(|| args.next())
+                    .ok_or(format!("Option \"{}\" needs a value", option));
+                match name {
+                    "help" => return Ok(Invocation::Help),
+                    "version" => return Ok(Invocation::Version),
+                    "color" | "colour" => config.color = ColorChoice::from_name(value.as_deref().unwrap_or("auto"))?,
+                    "threads" => match parse_number(&option, &required_value()?)? {
+                        0 => return Err(format!("Option \"{}\" needs at least 1 thread", option)),
+                        threads => config.threads = Some(threads)
+                    },
+                    "after-context" => config.after_context = parse_number(&option, &required_value()?)?,
+                    "before-context" => config.before_context = parse_number(&option, &required_value()?)?,
+                    "context" => {
+                        let lines = parse_number(&option, &required_value()?)?;
+                        config.after_context = lines;
+                        config.before_context = lines;
+                    }
+                    "max-count" => config.max_count = Some(parse_number(&option, &required_value()?)?),
+                    "regexp" => patterns.get_or_insert_with(Vec::new).push(required_value()?),
+                    "file" => patterns.get_or_insert_with(Vec::new).extend(read_pattern_file(&required_value()?)?),
+                    _ if value.is_some() => {
+                        return Err(format!("Option \"{}\" doesn't take a value", option));
+                    }
+                    "ignore-case" => ignore_case = Some(true),
+                    "no-ignore-case" => ignore_case = Some(false),
+                    "regex" => config.regex = true,
+                    "word-regexp" => config.whole_words = true,
+                    "line-regexp" => config.whole_lines = true,
+                    "invert-match" => config.invert_match = true,
+                    "line-number" => config.line_numbers = true,
+                    "byte-offset" => config.byte_offset = true,
+                    "count" => config.count = true,
+                    "files-with-matches" => config.files_with_matches = true,
+                    "files-without-match" => config.files_without_match = true,
+                    "quiet" | "silent" => config.quiet = true,
+                    "all-match" => config.match_all = true,
+                    _ => return Err(format!("Unknown option \"{}\"", option))
+                }
+            } else {
+                for (index, flag) in arg.char_indices().skip(1) {
+                    // As with grep, an option that needs a value takes the rest of the group (e.g. "-A3"), or the
+                    //  next argument if there's nothing left
+                    let rest = &arg[index + flag.len_utf8()..];
+                    let option = format!("-{}", flag);
+                    let mut required_value = || if rest.is_empty() { args.next() } else { Some(rest.to_string()) }
+                        .ok_or(format!("Option \"{}\" needs a value", option));
+                    match flag {
+                        'A' => config.after_context = parse_number(&option, &required_value()?)?,
+                        'B' => config.before_context = parse_number(&option, &required_value()?)?,
+                        'C' => {
+                            let lines = parse_number(&option, &required_value()?)?;
+                            config.after_context = lines;
+                            config.before_context = lines;
+                        }
+                        'm' => config.max_count = Some(parse_number(&option, &required_value()?)?),
+                        'e' => patterns.get_or_insert_with(Vec::new).push(required_value()?),
+                        'f' => patterns.get_or_insert_with(Vec::new).extend(read_pattern_file(&required_value()?)?),
+                        'h' => return Ok(Invocation::Help),
+                        'V' => return Ok(Invocation::Version),
+                        'i' => ignore_case = Some(true),
+                        'E' => config.regex = true,
+                        'w' => config.whole_words = true,
+                        'x' => config.whole_lines = true,
+                        'v' => config.invert_match = true,
+                        'n' => config.line_numbers = true,
+                        'b' => config.byte_offset = true,
+                        'c' => config.count = true,
+                        'l' => config.files_with_matches = true,
+                        'L' => config.files_without_match = true,
+                        'q' => config.quiet = true,
+                        _ => return Err(format!("Unknown option \"{}\" in \"{}\"", option, arg))
+                    }
+                    if "ABCefm".contains(flag) {
+                        break;
+                    }
+                }
+            }
+        }
 
-        let filename = match args.next() {
-            Some(arg) => arg,
-            None => return Err("Didn't get a file name")
+        let mut positional = positional.into_iter();
+        config.patterns = match patterns {
+            Some(patterns) => patterns,
+            None => match positional.next() {
+                Some(arg) => vec![arg],
+                None => return Err("Didn't get a query string".to_string())
+            }
         };
 
-        let case_sensitive = env_var_source.get_var("CASE_INSENSITIVE").is_err();
+        // Any number of files and directories may follow the query - with none, standard input is searched
+        config.paths = positional.collect();
+        if config.paths.is_empty() {
+            config.paths.push(STDIN_PATH.to_string());
+        }
 
-        Ok(Config { query, filename, case_sensitive })
+        config.case_sensitive = match ignore_case {
+            Some(ignore_case) => !ignore_case,
+            None => env_var_source.get_var("CASE_INSENSITIVE").is_err()
+        };
+
+        Ok(Invocation::Search(config))
     }
 
-    pub fn new<I: Iterator<Item = String>>(args: I) -> Result<Config, &'static str> {
-        Config::new_specifying_var_source(args, EnvironmentVariableAccessor{})
+    pub fn new<I: Iterator<Item = String>>(args: I) -> Result<Invocation, String> {
+        Invocation::new_specifying_var_source(args, EnvironmentVariableAccessor{})
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, MockVariableAccess, EnvVarResult};
+    use super::{ColorChoice, Config, Invocation, MockVariableAccess, EnvVarResult};
     use mockall::predicate::eq;
-    use std::env::VarError;
+    use std::env::{self, VarError};
+    use std::fs;
+
+    fn args(values: &[&str]) -> std::vec::IntoIter<String> {
+        values.iter().map(|value| value.to_string()).collect::<Vec<String>>().into_iter()
+    }
+
+    fn unset_env_var() -> MockVariableAccess {
+        let mut mock_var_access = MockVariableAccess::new();
+        mock_var_access.expect_get_var().return_const(Err(VarError::NotPresent));
+        mock_var_access
+    }
+
+    fn parse_search(values: &[&str]) -> Config {
+        match Invocation::new_specifying_var_source(args(values), unset_env_var()) {
+            Ok(Invocation::Search(config)) => config,
+            other => panic!("Expected a search, got {:?}", other)
+        }
+    }
 
     fn run_test_parse_valid_args(case_insensitive_env_arg_return: EnvVarResult, expected_case_sensitive: bool) {
-        
+
         let mut args = vec!["minigrep".to_string(), "some query".to_string(), "filename.type".to_string()];
 
         let mut mock_var_access = MockVariableAccess::new();
@@ -68,14 +319,13 @@ mod tests {
             .return_const(case_insensitive_env_arg_return);
 
         let expected = Config {
-            query: "some query".to_string(),
-            filename: "filename.type".to_string(),
-            case_sensitive: expected_case_sensitive
+            case_sensitive: expected_case_sensitive,
+            ..Config::new("some query".to_string(), vec!["filename.type".to_string()])
         };
 
         assert_eq!(
-            Config::new_specifying_var_source(args.drain(..), mock_var_access),
-            Ok(expected)
+            Invocation::new_specifying_var_source(args.drain(..), mock_var_access),
+            Ok(Invocation::Search(expected))
         );
     }
 
@@ -91,8 +341,8 @@ mod tests {
 
     fn run_test_missing_arg(mut args: Vec<String>, expected_error_message: &'static str) {
         assert_eq!(
-            Config::new_specifying_var_source(args.drain(..), MockVariableAccess::new()),
-            Err(expected_error_message)
+            Invocation::new_specifying_var_source(args.drain(..), MockVariableAccess::new()),
+            Err(expected_error_message.to_string())
         );
     }
 
@@ -102,9 +352,152 @@ mod tests {
     }
 
     #[test]
-    fn test_config_parsing_should_error_on_missing_filename_arg() {
-        run_test_missing_arg(
-            vec!["minigrep".to_string(), "some query".to_string()], "Didn't get a file name"
+    fn test_config_should_search_stdin_without_a_filename_arg() {
+        assert_eq!(parse_search(&["minigrep", "some query"]).paths, vec!["-".to_string()]);
+        assert_eq!(parse_search(&["minigrep", "some query", "-"]).paths, vec!["-".to_string()]);
+    }
+
+    #[test]
+    fn test_config_should_enable_regex_mode_from_flag_before_query() {
+        for flag in &["-E", "--regex"] {
+            let config = parse_search(&["minigrep", *flag, "a+b", "poem.txt"]);
+            assert!(config.regex);
+            assert_eq!(config.patterns, vec!["a+b".to_string()]);
+        }
+    }
+
+    #[test]
+    fn test_config_should_collect_every_path_after_the_query() {
+        let config = parse_search(&["minigrep", "some query", "src", "poem.txt"]);
+        assert_eq!(config.paths, vec!["src".to_string(), "poem.txt".to_string()]);
+    }
+
+    #[test]
+    fn test_config_should_parse_combined_short_flags_anywhere() {
+        let config = parse_search(&["minigrep", "-vn", "query", "-clx", "poem.txt", "-w"]);
+        let expected = Config {
+            whole_lines: true,
+            invert_match: true,
+            line_numbers: true,
+            count: true,
+            files_with_matches: true,
+            whole_words: true,
+            ..Config::new("query".to_string(), vec!["poem.txt".to_string()])
+        };
+        assert_eq!(config, expected);
+    }
+
+    #[test]
+    fn test_config_should_parse_long_flags() {
+        let config = parse_search(&[
+            "minigrep", "--invert-match", "--line-number", "--count", "--files-with-matches", "--word-regexp",
+            "--line-regexp", "--color=never", "query", "poem.txt",
+        ]);
+        assert!(config.invert_match && config.line_numbers && config.count && config.files_with_matches);
+        assert!(config.whole_words && config.whole_lines);
+        assert_eq!(config.color, ColorChoice::Never);
+        assert_eq!(parse_search(&["minigrep", "--color", "query", "poem.txt"]).color, ColorChoice::Auto);
+    }
+
+    #[test]
+    fn test_config_should_collect_queries_from_every_e_and_f_option() {
+        let pattern_file = env::temp_dir().join(format!("minigrep-patterns-{}.txt", std::process::id()));
+        fs::write(&pattern_file, "E1042\r\nE2001\n").unwrap();
+
+        let config = parse_search(&[
+            "minigrep", "-e", "WARN", "-f", pattern_file.to_str().unwrap(), "--regexp=FATAL", "-neE9", "app.log",
+        ]);
+        let patterns: Vec<String> = ["WARN", "E1042", "E2001", "FATAL", "E9"].iter().map(|p| p.to_string()).collect();
+        let paths = vec!["app.log".to_string()];
+        assert_eq!(config, Config { patterns, line_numbers: true, ..Config::new(String::new(), paths) });
+        fs::remove_file(&pattern_file).unwrap();
+
+        let config = parse_search(&["minigrep", "--all-match", "-e", "ERROR", "-e", "disk"]);
+        assert!(config.match_all);
+        assert_eq!(config.paths, vec!["-".to_string()]);
+        let error = Invocation::new_specifying_var_source(args(&["minigrep", "-f", "missing.txt"]), unset_env_var());
+        assert!(error.unwrap_err().starts_with("Couldn't read pattern file \"missing.txt\": "));
+    }
+
+    #[test]
+    fn test_config_should_parse_listing_limit_and_quiet_options() {
+        let config = parse_search(&["minigrep", "-qLm", "3", "query"]);
+        assert!(config.quiet && config.files_without_match);
+        assert_eq!(config.max_count, Some(3));
+        let config = parse_search(&["minigrep", "--max-count=0", "--silent", "--files-without-match", "query"]);
+        assert!(config.quiet && config.files_without_match);
+        assert_eq!(config.max_count, Some(0));
+        assert!(parse_search(&["minigrep", "--quiet", "query"]).quiet);
+        assert_eq!(parse_search(&["minigrep", "-m2", "query"]).max_count, Some(2));
+    }
+
+    #[test]
+    fn test_case_flags_should_override_env_var() {
+        let mut env_var_set = MockVariableAccess::new();
+        env_var_set.expect_get_var().times(0);
+        let invocation = Invocation::new_specifying_var_source(
+            args(&["minigrep", "--no-ignore-case", "query", "poem.txt"]), env_var_set
+        );
+        assert!(matches!(invocation, Ok(Invocation::Search(Config { case_sensitive: true, .. }))));
+
+        assert!(!parse_search(&["minigrep", "-i", "query", "poem.txt"]).case_sensitive);
+    }
+
+    #[test]
+    fn test_double_dash_should_end_options() {
+        let config = parse_search(&["minigrep", "-n", "--", "-v", "--help"]);
+        assert_eq!(config, Config { line_numbers: true, ..Config::new("-v".to_string(), vec!["--help".to_string()]) });
+    }
+
+    #[test]
+    fn test_config_should_parse_context_options_in_every_form() {
+        let context = |values: &[&str]| {
+            let config = parse_search(values);
+            (config.before_context, config.after_context)
+        };
+        assert_eq!(context(&["minigrep", "-A", "2", "query"]), (0, 2));
+        assert_eq!(context(&["minigrep", "-nB3", "query"]), (3, 0));
+        assert_eq!(context(&["minigrep", "-C1", "-A", "4", "query"]), (1, 4));
+        assert_eq!(context(&["minigrep", "--context=2", "--before-context", "5", "query"]), (5, 2));
+        assert_eq!(context(&["minigrep", "--after-context=1", "query"]), (0, 1));
+        assert!(parse_search(&["minigrep", "-bnC1", "query"]).byte_offset);
+    }
+
+    #[test]
+    fn test_config_should_parse_thread_count() {
+        assert_eq!(parse_search(&["minigrep", "query", "src"]).threads, None);
+        assert_eq!(parse_search(&["minigrep", "--threads", "4", "query", "src"]).threads, Some(4));
+        assert_eq!(parse_search(&["minigrep", "--threads=1", "query", "src"]).threads, Some(1));
+        assert_eq!(
+            Invocation::new_specifying_var_source(args(&["minigrep", "--threads=0", "q"]), MockVariableAccess::new()),
+            Err("Option \"--threads\" needs at least 1 thread".to_string())
+        );
+    }
+
+    #[test]
+    fn test_help_and_version_should_win_over_other_args() {
+        let parse = |values: &[&str]| Invocation::new_specifying_var_source(args(values), MockVariableAccess::new());
+        assert_eq!(parse(&["minigrep", "-i", "--help"]), Ok(Invocation::Help));
+        assert_eq!(parse(&["minigrep", "-nh"]), Ok(Invocation::Help));
+        assert_eq!(parse(&["minigrep", "--version", "query"]), Ok(Invocation::Version));
+        assert_eq!(parse(&["minigrep", "-V"]), Ok(Invocation::Version));
+    }
+
+    #[test]
+    fn test_config_parsing_should_error_on_invalid_options() {
+        let parse = |values: &[&str]| Invocation::new_specifying_var_source(args(values), MockVariableAccess::new());
+        assert_eq!(parse(&["minigrep", "-nz", "query"]), Err("Unknown option \"-z\" in \"-nz\"".to_string()));
+        assert_eq!(parse(&["minigrep", "--frobnicate"]), Err("Unknown option \"--frobnicate\"".to_string()));
+        assert_eq!(parse(&["minigrep", "--count=3"]), Err("Option \"--count\" doesn't take a value".to_string()));
+        assert_eq!(parse(&["minigrep", "-A"]), Err("Option \"-A\" needs a value".to_string()));
+        assert_eq!(parse(&["minigrep", "--context"]), Err("Option \"--context\" needs a value".to_string()));
+        assert_eq!(
+            parse(&["minigrep", "-C", "two", "query"]),
+            Err("Option \"-C\" needs a whole number, not \"two\"".to_string())
+        );
+        assert_eq!(
+            parse(&["minigrep", "--color=sometimes"]),
+            Err("Invalid colour choice \"sometimes\" - expected always, never or auto".to_string())
         );
     }
 
@@ -0,0 +1,199 @@
+use std::ops::Range;
+
+// Boyer-Moore-Horspool search for a single literal - the shift table is worked out once, then each attempt compares
+//  the last byte of the window first and skips ahead by up to the pattern's length when it doesn't match
+pub struct Horspool {
+    pattern: Vec<u8>,
+    shifts: [usize; 256],
+}
+
+impl Horspool {
+    pub fn new(pattern: &[u8]) -> Horspool {
+        let mut shifts = [pattern.len(); 256];
+        if let Some((_, init)) = pattern.split_last() {
+            for (index, &byte) in init.iter().enumerate() {
+                shifts[byte as usize] = init.len() - index;
+            }
+        }
+        Horspool { pattern: pattern.to_vec(), shifts }
+    }
+
+    // The start of the first occurrence of the pattern in the haystack
+    pub fn find(&self, haystack: &[u8]) -> Option<usize> {
+        let (&last, init) = match self.pattern.split_last() {
+            Some(split) => split,
+            None => return Some(0),
+        };
+        let mut start = 0;
+        while start + self.pattern.len() <= haystack.len() {
+            let window_last = haystack[start + init.len()];
+            if window_last == last && &haystack[start..start + init.len()] == init {
+                return Some(start);
+            }
+            start += self.shifts[window_last as usize];
+        }
+        None
+    }
+}
+
+// Aho-Corasick search for many literals at once, in a single pass over the haystack. The trie of patterns is turned
+//  into a complete state machine (every state has a transition for every byte, following failure links where the
+//  trie has none), so scanning is one table lookup per byte however many patterns there are.
+pub struct AhoCorasick {
+    transitions: Vec<u32>,
+    // The longest pattern (index and length) that ends on reaching each state, if any
+    matches: Vec<Option<(usize, usize)>>,
+    empty_pattern: Option<usize>,
+}
+
+const NO_STATE: u32 = u32::MAX;
+
+impl AhoCorasick {
+    pub fn new<P: AsRef<[u8]>>(patterns: &[P]) -> AhoCorasick {
+        let mut transitions = vec![NO_STATE; 256];
+        let mut matches = vec![None];
+        let mut empty_pattern = None;
+
+        for (pattern_index, pattern) in patterns.iter().enumerate() {
+            let pattern = pattern.as_ref();
+            if pattern.is_empty() {
+                empty_pattern = empty_pattern.or(Some(pattern_index));
+            }
+            let mut state = 0;
+            for &byte in pattern {
+                let next = transitions[state * 256 + byte as usize];
+                state = if next == NO_STATE {
+                    let new_state = matches.len();
+                    transitions[state * 256 + byte as usize] = new_state as u32;
+                    transitions.extend_from_slice(&[NO_STATE; 256]);
+                    matches.push(None);
+                    new_state
+                } else {
+                    next as usize
+                };
+            }
+            if matches[state].is_none() && !pattern.is_empty() {
+                matches[state] = Some((pattern_index, pattern.len()));
+            }
+        }
+
+        // Breadth first, so that each state's failure state is complete before it is needed
+        let mut failures = vec![0; matches.len()];
+        let mut queue = std::collections::VecDeque::new();
+        for transition in transitions[..256].iter_mut() {
+            match *transition {
+                NO_STATE => *transition = 0,
+                child => queue.push_back(child as usize),
+            }
+        }
+        while let Some(state) = queue.pop_front() {
+            for byte in 0..256 {
+                let failure_next = transitions[failures[state] * 256 + byte];
+                match transitions[state * 256 + byte] {
+                    NO_STATE => transitions[state * 256 + byte] = failure_next,
+                    child => {
+                        let child = child as usize;
+                        failures[child] = failure_next as usize;
+                        // A pattern ending here is longer than one inherited from the failure state
+                        matches[child] = matches[child].or(matches[failures[child]]);
+                        queue.push_back(child);
+                    }
+                }
+            }
+        }
+
+        AhoCorasick { transitions, matches, empty_pattern }
+    }
+
+    // The first match to end in the haystack, as the index of the pattern and where it is
+    pub fn find(&self, haystack: &[u8]) -> Option<(usize, Range<usize>)> {
+        if let Some(pattern_index) = self.empty_pattern {
+            return Some((pattern_index, 0..0));
+        }
+        let mut state = 0;
+        for (index, &byte) in haystack.iter().enumerate() {
+            state = self.transitions[state * 256 + byte as usize] as usize;
+            if let Some((pattern_index, length)) = self.matches[state] {
+                return Some((pattern_index, index + 1 - length..index + 1));
+            }
+        }
+        None
+    }
+}
+
+// Whichever engine suits the number of literals being searched for
+pub(crate) enum LiteralEngine {
+    Single(Box<Horspool>),
+    Many(AhoCorasick),
+}
+
+impl LiteralEngine {
+    pub(crate) fn new(patterns: &[String]) -> LiteralEngine {
+        match patterns {
+            [pattern] => LiteralEngine::Single(Box::new(Horspool::new(pattern.as_bytes()))),
+            _ => LiteralEngine::Many(AhoCorasick::new(patterns)),
+        }
+    }
+
+    // The start of the first match of any of the patterns in the haystack
+    pub(crate) fn find(&self, haystack: &[u8]) -> Option<usize> {
+        match self {
+            LiteralEngine::Single(engine) => engine.find(haystack),
+            LiteralEngine::Many(engine) => engine.find(haystack).map(|(_, found)| found.start),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AhoCorasick, Horspool};
+
+    #[test]
+    fn test_horspool_finds_first_occurrence() {
+        let haystack = b"Rust:\nsafe, fast, productive.\nPick three.\nDuct tape.";
+        assert_eq!(Horspool::new(b"duct").find(haystack), Some(21));
+        assert_eq!(Horspool::new(b"Duct").find(haystack), Some(42));
+        assert_eq!(Horspool::new(b"tape.").find(haystack), Some(47));
+        assert_eq!(Horspool::new(b"tapes").find(haystack), None);
+        assert_eq!(Horspool::new(b"").find(haystack), Some(0));
+        assert_eq!(Horspool::new(b"longer than the haystack").find(b"short"), None);
+        // Repeated bytes in the pattern mean smaller shifts - make sure none are skipped past
+        assert_eq!(Horspool::new(b"abab").find(b"aabaabababab"), Some(4));
+    }
+
+    #[test]
+    fn test_horspool_agrees_with_naive_search() {
+        let haystack = b"abracadabra abracadabra cadabra";
+        for start in 0..haystack.len() {
+            for end in start..(start + 8).min(haystack.len()) {
+                let pattern = &haystack[start..end];
+                let naive = (0..=haystack.len() - pattern.len()).find(|&i| &haystack[i..i + pattern.len()] == pattern);
+                assert_eq!(Horspool::new(pattern).find(haystack), naive, "{:?}", pattern);
+            }
+        }
+    }
+
+    #[test]
+    fn test_aho_corasick_finds_first_match_to_end() {
+        let searcher = AhoCorasick::new(&["E1042", "E2001", "WARN"]);
+        assert_eq!(searcher.find(b"INFO ok\nERROR E2001 disk full"), Some((1, 14..19)));
+        assert_eq!(searcher.find(b"WARN then E1042"), Some((2, 0..4)));
+        assert_eq!(searcher.find(b"E104 E200 WAR"), None);
+    }
+
+    #[test]
+    fn test_aho_corasick_follows_failure_links() {
+        let searcher = AhoCorasick::new(&["he", "she", "his", "hers"]);
+        assert_eq!(searcher.find(b"ushers"), Some((1, 1..4)));
+        assert_eq!(searcher.find(b"ahishers"), Some((2, 1..4)));
+        // "bc" is found inside the longer "abcd" that fails to complete
+        let searcher = AhoCorasick::new(&["abcd", "bc"]);
+        assert_eq!(searcher.find(b"abcx"), Some((1, 1..3)));
+    }
+
+    #[test]
+    fn test_aho_corasick_with_empty_pattern_matches_at_start() {
+        assert_eq!(AhoCorasick::new(&["abc", ""]).find(b"xyz"), Some((1, 0..0)));
+        assert_eq!(AhoCorasick::new::<&str>(&[]).find(b"xyz"), None);
+    }
+}
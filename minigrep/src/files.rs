@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use ignore::WalkBuilder;
+
+use crate::config::STDIN_PATH;
+
+// Same heuristic as grep - a NUL byte near the start of a file means it is binary rather than text
+const BINARY_CHECK_LENGTH: usize = 8000;
+
+#[derive(Eq, PartialEq, Debug)]
+pub(crate) enum Input {
+    Stdin,
+    File(PathBuf),
+}
+
+// Expands the given paths into the inputs to search, in a stable (sorted) order. Directories are walked recursively,
+//  skipping hidden files and anything excluded by .gitignore or .ignore files - even outside a git repository - but
+//  files named explicitly are always searched.
+pub(crate) fn inputs_to_search(paths: &[String]) -> Vec<Result<Input, String>> {
+    let mut inputs = vec![];
+    for path in paths {
+        if path == STDIN_PATH {
+            inputs.push(Ok(Input::Stdin));
+            continue;
+        }
+        let walker = WalkBuilder::new(path)
+            .require_git(false)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+        for entry in walker {
+            match entry {
+                Ok(entry) => {
+                    if entry.file_type().is_some_and(|file_type| file_type.is_file()) {
+                        inputs.push(Ok(Input::File(entry.into_path())));
+                    }
+                }
+                Err(e) => inputs.push(Err(e.to_string())),
+            }
+        }
+    }
+    inputs
+}
+
+pub(crate) fn is_binary(contents: &[u8]) -> bool {
+    contents.iter().take(BINARY_CHECK_LENGTH).any(|&byte| byte == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{inputs_to_search, is_binary, Input};
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn create_file(path: &PathBuf, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_directories_are_walked_recursively_respecting_ignore_files() {
+        let root = env::temp_dir().join(format!("minigrep-files-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        create_file(&root.join("b.txt"), "b");
+        create_file(&root.join("a/nested.txt"), "nested");
+        create_file(&root.join("a/output.log"), "ignored");
+        create_file(&root.join(".gitignore"), "*.log\n");
+        create_file(&root.join(".hidden"), "hidden");
+
+        let paths = vec![
+            root.to_str().unwrap().to_string(),
+            "-".to_string(),
+            root.join("a/output.log").to_str().unwrap().to_string(),
+        ];
+        let inputs: Vec<Input> = inputs_to_search(&paths).into_iter().map(Result::unwrap).collect();
+
+        assert_eq!(inputs, vec![
+            Input::File(root.join("a/nested.txt")),
+            Input::File(root.join("b.txt")),
+            Input::Stdin,
+            Input::File(root.join("a/output.log")),
+        ]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_missing_paths_are_reported() {
+        let inputs = inputs_to_search(&["no/such/path".to_string()]);
+        assert_eq!(inputs.len(), 1);
+        assert!(inputs[0].as_ref().unwrap_err().contains("no/such/path"));
+    }
+
+    #[test]
+    fn test_is_binary_detects_nul_bytes() {
+        assert!(!is_binary("Rust:\nsafe, fast, productive.".as_bytes()));
+        assert!(is_binary(b"\x7fELF\x02\x01\x01\x00\x00"));
+    }
+}
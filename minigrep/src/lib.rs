@@ -1,22 +1,283 @@
 mod search;
+mod searcher;
 mod config;
+mod engine;
+mod files;
 
-pub use config::Config;
+pub use config::{ColorChoice, Config, Invocation, USAGE};
+pub use engine::{AhoCorasick, Horspool};
+pub use search::{find_matches, Match};
 
+use std::collections::BTreeMap;
 use std::error::Error;
-use std::fs;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, IsTerminal, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
 
-pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
-    let contents = fs::read_to_string(config.filename)?;
+// Large enough that the binary file check sees a whole block in the first read
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+// How results from standard input are labelled, as in grep
+const STDIN_LABEL: &str = "(standard input)";
+// How much output all the worker threads may hold back between them, while waiting for the files before theirs
+const MAX_BUFFERED_OUTPUT: usize = 16 * 1024 * 1024;
 
-    let results = if config.case_sensitive {
-        search::search(&config.query, &contents)
+// A file's results as collected by a worker thread, or left for the thread writing the output to search itself
+enum Searched {
+    Buffered(Vec<u8>, Result<bool, String>),
+    Deferred,
+}
+
+// Collects a worker's results, failing the search once the shared limit on held back output would be passed
+struct LimitedBuffer<'a> {
+    bytes: Vec<u8>,
+    buffered: &'a AtomicUsize,
+    limit: usize,
+    over_limit: bool,
+}
+
+impl<'a> LimitedBuffer<'a> {
+    fn new(buffered: &'a AtomicUsize, limit: usize) -> LimitedBuffer<'a> {
+        LimitedBuffer { bytes: Vec::new(), buffered, limit, over_limit: false }
+    }
+}
+
+impl Write for LimitedBuffer<'_> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if self.buffered.fetch_add(data.len(), Ordering::Relaxed) + data.len() > self.limit {
+            self.buffered.fetch_sub(data.len(), Ordering::Relaxed);
+            self.over_limit = true;
+            return Err(io::Error::new(io::ErrorKind::OutOfMemory, "too much output held back"));
+        }
+        self.bytes.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Returns whether any line was selected (or, with -L, any file listed), for the exit status
+pub fn run(config: Config) -> Result<bool, Box<dyn Error>> {
+    let stdout = io::stdout();
+    let color = match config.color {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => stdout.is_terminal(),
+    };
+    let mut out = BufWriter::new(stdout.lock());
+    search_to(&config, color, &mut out)
+}
+
+// Searches every path in the config, writing results to `out` grouped by file, in the order the paths were given.
+//  Returns whether any line was selected, as `run` does.
+pub fn search_to<W: Write>(config: &Config, color: bool, out: &mut W) -> Result<bool, Box<dyn Error>> {
+    search_with_buffer_limit(config, color, out, MAX_BUFFERED_OUTPUT)
+}
+
+fn search_with_buffer_limit<W: Write>(
+    config: &Config, color: bool, out: &mut W, buffer_limit: usize
+) -> Result<bool, Box<dyn Error>> {
+    let matcher = search::Matcher::new(config)?;
+    let inputs = files::inputs_to_search(&config.paths);
+    // As with grep, results are only prefixed with their file when there could be more than one
+    let show_paths = inputs.len() > 1 || config.paths.iter().any(|path| Path::new(path).is_dir());
+    let threads = config.threads
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |threads| threads.get()))
+        .min(inputs.len());
+
+    // A file that can't be read shouldn't stop the rest being searched, but is still reported as a failure
+    let mut failures = 0;
+    let mut selected = false;
+    let mut report_failure = |out: &mut W, e: String| -> io::Result<()> {
+        out.flush()?;
+        eprintln!("{}", e);
+        failures += 1;
+        Ok(())
+    };
+
+    if threads <= 1 {
+        // Results go straight to the output, so a single huge file never has to be held in memory
+        for input in inputs {
+            match input.and_then(|input| search_input(config, &matcher, &input, show_paths, color, out)) {
+                Ok(input_selected) => selected |= input_selected,
+                Err(e) => report_failure(out, e)?,
+            }
+            // With only an exit status wanted, the first selected line settles it
+            if selected && config.quiet {
+                break;
+            }
+        }
     } else {
-        search::search_case_insensitive(&config.query, &contents)
+        // Each file's results are collected by whichever worker searches it, then written out in input order as soon
+        //  as all the files before it are done. So that memory use stays bounded, a file whose results would take the
+        //  held back output over the limit is searched again, straight to the output, once its turn comes - as is
+        //  standard input, which can only be read once.
+        let next_input = AtomicUsize::new(0);
+        let buffered = AtomicUsize::new(0);
+        let finished_early = AtomicBool::new(false);
+        let (sender, receiver) = mpsc::channel();
+        let (inputs, next_input, matcher, finished_early) = (&inputs, &next_input, &matcher, &finished_early);
+        let buffered = &buffered;
+        thread::scope(|scope| -> io::Result<()> {
+            for _ in 0..threads {
+                let sender = sender.clone();
+                scope.spawn(move || loop {
+                    if finished_early.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let index = next_input.fetch_add(1, Ordering::Relaxed);
+                    let input = match inputs.get(index) {
+                        Some(input) => input,
+                        None => break,
+                    };
+                    let mut results = LimitedBuffer::new(buffered, buffer_limit);
+                    let searched = match input {
+                        Ok(files::Input::Stdin) => Searched::Deferred,
+                        Ok(input) => {
+                            let outcome = search_input(config, matcher, input, show_paths, color, &mut results);
+                            if results.over_limit {
+                                buffered.fetch_sub(results.bytes.len(), Ordering::Relaxed);
+                                Searched::Deferred
+                            } else {
+                                Searched::Buffered(results.bytes, outcome)
+                            }
+                        }
+                        Err(e) => Searched::Buffered(Vec::new(), Err(e.clone())),
+                    };
+                    if sender.send((index, searched)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(sender);
+
+            let mut finished = BTreeMap::new();
+            let mut next_to_write = 0;
+            for (index, searched) in receiver {
+                finished.insert(index, searched);
+                while let Some(searched) = finished.remove(&next_to_write) {
+                    let outcome = match (searched, &inputs[next_to_write]) {
+                        (Searched::Buffered(results, outcome), _) => {
+                            out.write_all(&results)?;
+                            buffered.fetch_sub(results.len(), Ordering::Relaxed);
+                            outcome
+                        }
+                        (Searched::Deferred, Ok(input)) => search_input(config, matcher, input, show_paths, color, out),
+                        (Searched::Deferred, Err(e)) => Err(e.clone()),
+                    };
+                    match outcome {
+                        Ok(input_selected) => selected |= input_selected,
+                        Err(e) => report_failure(out, e)?,
+                    }
+                    next_to_write += 1;
+                }
+                if selected && config.quiet {
+                    finished_early.store(true, Ordering::Relaxed);
+                }
+            }
+            Ok(())
+        })?;
+    }
+
+    out.flush()?;
+    // As in grep, a selected line is all that matters to -q, even if some files couldn't be read
+    if failures > 0 && !(selected && config.quiet) {
+        return Err(format!("{} path(s) could not be searched", failures).into());
+    }
+    Ok(selected)
+}
+
+fn search_input<W: Write>(
+    config: &Config, matcher: &search::Matcher, input: &files::Input, show_path: bool, color: bool, out: &mut W
+) -> Result<bool, String> {
+    let label = match input {
+        files::Input::Stdin => STDIN_LABEL.to_string(),
+        files::Input::File(path) => path.display().to_string(),
+    };
+    // Listing files always needs the path, even for a single file
+    let listing_files = config.files_with_matches || config.files_without_match;
+    let prefix_label = if show_path || listing_files { Some(label.as_str()) } else { None };
+    let result = match input {
+        files::Input::Stdin => {
+            let stdin = io::stdin();
+            searcher::search_reader(config, matcher, stdin.lock(), prefix_label, color, out)
+        }
+        files::Input::File(path) => File::open(path).and_then(|file| {
+            let reader = BufReader::with_capacity(READ_BUFFER_SIZE, file);
+            searcher::search_reader(config, matcher, reader, prefix_label, color, out)
+        }),
     };
+    result.map_err(|e| format!("{}: {}", label, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{search_to, search_with_buffer_limit};
+    use crate::Config;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_output_is_grouped_by_file_in_input_order_whatever_the_thread_count() {
+        let root = env::temp_dir().join(format!("minigrep-threads-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let mut paths = vec![];
+        for file_number in 0..20 {
+            let path = root.join(format!("file-{:02}.txt", file_number));
+            let lines: Vec<String> = (0..50).map(|line| format!("needle {} {}\nhay", file_number, line)).collect();
+            fs::write(&path, lines.join("\n")).unwrap();
+            // Given in reverse, to check the order given is kept rather than the order the walk finds them in
+            paths.insert(0, path.to_str().unwrap().to_string());
+        }
+
+        let search = |threads| {
+            let config = Config { threads: Some(threads), ..Config::new("needle".to_string(), paths.clone()) };
+            let mut out = Vec::new();
+            search_to(&config, false, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        let single_threaded = search(1);
+        assert_eq!(single_threaded.lines().count(), 20 * 50);
+        assert!(single_threaded.starts_with(&format!("{}:needle 19 0\n", paths[0])));
+        assert_eq!(search(8), single_threaded);
+        // With too little room to hold back every file's results, some are searched again when their turn comes
+        let config = Config { threads: Some(8), ..Config::new("needle".to_string(), paths.clone()) };
+        let mut out = Vec::new();
+        search_with_buffer_limit(&config, false, &mut out, 4000).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), single_threaded);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_quiet_search_succeeds_on_a_match_despite_unreadable_paths() {
+        let root = env::temp_dir().join(format!("minigrep-quiet-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let poem = root.join("poem.txt");
+        fs::write(&poem, "How dreary to be somebody!").unwrap();
+        let paths = vec![root.join("missing.txt").to_str().unwrap().to_string(), poem.to_str().unwrap().to_string()];
+
+        let search = |config: Config| {
+            let mut out = Vec::new();
+            let result = search_to(&config, false, &mut out).map_err(|e| e.to_string());
+            (result, String::from_utf8(out).unwrap())
+        };
+        for threads in [1, 2].iter() {
+            let quiet = |query: &str| {
+                Config { quiet: true, threads: Some(*threads), ..Config::new(query.to_string(), paths.clone()) }
+            };
+            assert_eq!(search(quiet("dreary")), (Ok(true), String::new()));
+            assert_eq!(search(quiet("frog")), (Err("1 path(s) could not be searched".to_string()), String::new()));
+        }
+        let not_quiet = Config::new("frog".to_string(), vec![poem.to_str().unwrap().to_string()]);
+        assert_eq!(search(not_quiet), (Ok(false), String::new()));
 
-    for line in results {
-        println!("{}", line);
+        fs::remove_dir_all(&root).unwrap();
     }
-    Ok(())
 }
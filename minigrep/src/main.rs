@@ -1,18 +1,37 @@
 use std::{env, process};
 
-use minigrep::Config;
+use minigrep::{Invocation, USAGE};
+
+// Exit statuses, as in grep
+const NOTHING_SELECTED: i32 = 1;
+const ERROR: i32 = 2;
 
 fn main() {
 
-    let config = Config::new(env::args()).unwrap_or_else(|err| {
+    let invocation = Invocation::new(env::args()).unwrap_or_else(|err| {
         eprintln!("Problem parsing arguments: {}", err);
-        process::exit(1);
+        eprintln!("Run \"minigrep --help\" for usage");
+        process::exit(ERROR);
     });
 
-    println!("Searching for {} (case sensitive = {}) in file {}", config.query, config.case_sensitive, config.filename);
+    let config = match invocation {
+        Invocation::Search(config) => config,
+        Invocation::Help => {
+            println!("{}", USAGE);
+            return;
+        }
+        Invocation::Version => {
+            println!("minigrep {}", env!("CARGO_PKG_VERSION"));
+            return;
+        }
+    };
 
-    if let Err(e) = minigrep::run(config) {
-        eprintln!("Application error: {}", e);
-        process::exit(1);
+    match minigrep::run(config) {
+        Ok(true) => {}
+        Ok(false) => process::exit(NOTHING_SELECTED),
+        Err(e) => {
+            eprintln!("Application error: {}", e);
+            process::exit(ERROR);
+        }
     }
 }
@@ -1,25 +1,292 @@
+use std::iter;
+use std::ops::Range;
 
-pub(crate) fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
-    // A more declarative version than initially provided in the tutorial -
-    // later asked to implement basically exactly this in 13.3
-    contents
-        .lines()
-        .filter(|l| l.contains(query))
-        .collect()
+use caseless::Caseless;
+use regex::{Regex, RegexBuilder};
+use regex_syntax::is_word_character;
+
+use crate::engine::LiteralEngine;
+use crate::Config;
+
+// A selected line, with where it was found and which parts of it matched
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Match<'a> {
+    // Counting from 1
+    pub line_number: usize,
+    // Of the start of the line, from the start of the input
+    pub byte_offset: usize,
+    pub line: &'a str,
+    // Byte ranges within the line, in order - empty when lines are selected by not matching
+    pub spans: Vec<Range<usize>>,
+}
+
+// Finds the lines selected by the config in text that is already in memory, for use as a library
+pub fn find_matches<'a>(config: &Config, contents: &'a str) -> Result<Vec<Match<'a>>, String> {
+    let matcher = Matcher::new(config)?;
+    let mut matches = vec![];
+    let mut byte_offset = 0;
+    for (index, line_with_ending) in contents.split_inclusive('\n').enumerate() {
+        let line = line_with_ending.strip_suffix('\n').unwrap_or(line_with_ending);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if matcher.is_match(line) != config.invert_match {
+            let spans = if config.invert_match { vec![] } else { matcher.find_spans(line) };
+            matches.push(Match { line_number: index + 1, byte_offset, line, spans });
+        }
+        byte_offset += line_with_ending.len();
+    }
+    Ok(matches)
+}
+
+// How lines are matched, decided once up front so that an invalid pattern is reported before any file is searched
+pub(crate) struct Matcher {
+    patterns: Vec<Pattern>,
+    match_all: bool,
+    // For finding lines that match any of the patterns in a whole block of text at once, when that's possible
+    block_engine: Option<LiteralEngine>,
+}
+
+struct Pattern {
+    finder: Finder,
+    extent: Extent,
+}
+
+enum Finder {
+    Literal(String),
+    LiteralCaseInsensitive { query_folded: String },
+    Regex(Regex),
+}
+
+// How much of the line a match has to cover
+#[derive(Clone, Copy, PartialEq)]
+enum Extent {
+    Anywhere,
+    // With no word character on either side
+    WholeWord,
+    WholeLine,
+}
+
+impl Matcher {
+    pub(crate) fn new(config: &Config) -> Result<Matcher, String> {
+        let patterns = config.patterns.iter()
+            .map(|query| Pattern::new(config, query))
+            .collect::<Result<Vec<Pattern>, String>>()?;
+        // Only plain literals can be found by the block engines, and they can't see matches that cross lines. With
+        //  more than one pattern they only find lines that match any of them, so can't be used to match all of them.
+        let is_literal = |pattern: &Pattern| match &pattern.finder {
+            Finder::Literal(query) => pattern.extent == Extent::Anywhere && !query.contains('\n'),
+            _ => false,
+        };
+        let block_engine = if !patterns.is_empty() && patterns.iter().all(is_literal)
+            && (patterns.len() == 1 || !config.match_all)
+        {
+            Some(LiteralEngine::new(&config.patterns))
+        } else {
+            None
+        };
+        Ok(Matcher { patterns, match_all: config.match_all, block_engine })
+    }
+
+    pub(crate) fn is_match(&self, line: &str) -> bool {
+        if self.match_all {
+            !self.patterns.is_empty() && self.patterns.iter().all(|pattern| pattern.is_match(line))
+        } else {
+            self.patterns.iter().any(|pattern| pattern.is_match(line))
+        }
+    }
+
+    pub(crate) fn block_engine(&self) -> Option<&LiteralEngine> {
+        self.block_engine.as_ref()
+    }
+
+    pub(crate) fn find_spans(&self, line: &str) -> Vec<Range<usize>> {
+        if let [pattern] = self.patterns.as_slice() {
+            return pattern.find_spans(line);
+        }
+        // Matches of different patterns can overlap, so they are put in order and overlaps merged into one span
+        let mut spans: Vec<Range<usize>> = self.patterns.iter().flat_map(|pattern| pattern.find_spans(line)).collect();
+        spans.sort_by_key(|span| (span.start, span.end));
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(spans.len());
+        for span in spans {
+            match merged.last_mut() {
+                Some(last) if span.start < last.end || span == *last => last.end = last.end.max(span.end),
+                _ => merged.push(span),
+            }
+        }
+        merged
+    }
+}
+
+impl Pattern {
+    fn new(config: &Config, query: &str) -> Result<Pattern, String> {
+        // As in grep, -x wins over -w
+        let extent = if config.whole_lines {
+            Extent::WholeLine
+        } else if config.whole_words {
+            Extent::WholeWord
+        } else {
+            Extent::Anywhere
+        };
+        let finder = if config.regex {
+            // A regex has to be anchored to match a whole line, as it could match a shorter part of it first. Likewise
+            //  for whole words, so that "foo|foobar" goes on to try "foobar" when "foo" is only part of a word - the
+            //  half boundaries only check there is no word character outside the match, as is_whole_word does.
+            let pattern = match extent {
+                Extent::WholeLine => format!("^(?:{})$", query),
+                Extent::WholeWord => format!(r"\b{{start-half}}(?:{})\b{{end-half}}", query),
+                Extent::Anywhere => query.to_string(),
+            };
+            // Case-insensitivity is left to the regex engine rather than lowercasing every line
+            let regex = RegexBuilder::new(&pattern)
+                .case_insensitive(!config.case_sensitive)
+                .build()
+                .map_err(|e| format!("Invalid regular expression \"{}\": {}", query, e))?;
+            Finder::Regex(regex)
+        } else if config.case_sensitive {
+            Finder::Literal(query.to_string())
+        } else {
+            Finder::LiteralCaseInsensitive { query_folded: query.chars().flat_map(fold_char).collect() }
+        };
+        Ok(Pattern { finder, extent })
+    }
+
+    fn is_match(&self, line: &str) -> bool {
+        match (&self.finder, self.extent) {
+            (Finder::Literal(query), Extent::Anywhere) => line.contains(query.as_str()),
+            (Finder::Regex(regex), Extent::Anywhere) => regex.is_match(line),
+            _ => self.find_at(line, 0).is_some(),
+        }
+    }
+
+    fn find_spans(&self, line: &str) -> Vec<Range<usize>> {
+        let mut spans = vec![];
+        let mut search_from = 0;
+        while let Some(span) = self.find_at(line, search_from) {
+            // An empty match would be found again from the same place, so needs moving on by hand
+            search_from = if span.is_empty() { next_char_boundary(line, span.end) } else { span.end };
+            spans.push(span);
+            if search_from > line.len() {
+                break;
+            }
+        }
+        spans
+    }
+
+    // The first match starting at or after byte `search_from` that covers enough of the line
+    fn find_at(&self, line: &str, search_from: usize) -> Option<Range<usize>> {
+        match self.extent {
+            Extent::Anywhere => self.finder.find_at(line, search_from),
+            Extent::WholeWord if matches!(self.finder, Finder::Regex(_)) => self.finder.find_at(line, search_from),
+            // Whatever the finder matches at the start of the line is the only match there could be at the start -
+            //  a fixed length for literals, and the whole line for the anchored regex
+            Extent::WholeLine if search_from == 0 => {
+                self.finder.find_at(line, 0).filter(|span| *span == (0..line.len()))
+            }
+            Extent::WholeLine => None,
+            // A match that is part of a longer word is passed over for the next one, which may overlap it - so
+            //  "aa" is found as a whole word in "aaa aa", at the second attempt after the first
+            Extent::WholeWord => {
+                let mut search_from = search_from;
+                while let Some(span) = self.finder.find_at(line, search_from) {
+                    if is_whole_word(line, &span) {
+                        return Some(span);
+                    }
+                    search_from = next_char_boundary(line, span.start);
+                    if search_from > line.len() {
+                        break;
+                    }
+                }
+                None
+            }
+        }
+    }
 }
 
-pub(crate) fn search_case_insensitive<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
-    let query_lowercase = query.to_lowercase();
-    contents
-        .lines()
-        .filter(|l| l.to_lowercase().contains(&query_lowercase))
-        .collect()
+impl Finder {
+    fn find_at(&self, line: &str, search_from: usize) -> Option<Range<usize>> {
+        match self {
+            Finder::Literal(query) => line[search_from..].find(query.as_str()).map(|start| {
+                let start = search_from + start;
+                start..start + query.len()
+            }),
+            Finder::LiteralCaseInsensitive { query_folded } => find_case_insensitive(line, query_folded, search_from),
+            // Searching from a position rather than slicing the line keeps anchors and word boundaries right
+            Finder::Regex(regex) => regex.find_at(line, search_from).map(|found| found.range()),
+        }
+    }
+}
+
+// Using the same Unicode definition of a word character as \w in a regex - letters, marks, digits and connecting
+//  punctuation such as "_"
+fn is_whole_word(line: &str, span: &Range<usize>) -> bool {
+    let before = line[..span.start].chars().next_back();
+    let after = line[span.end..].chars().next();
+    !before.is_some_and(is_word_character) && !after.is_some_and(is_word_character)
+}
+
+// The position of the char after the one at `index`, or just past the end of the line if there isn't one
+fn next_char_boundary(line: &str, index: usize) -> usize {
+    line[index..].chars().next().map_or(line.len() + 1, |c| index + c.len_utf8())
+}
+
+// Full Unicode case folding (so "ß" folds to "ss", and final "ς" to "σ"), with no language-specific rules - so the
+//  Turkish dotted and dotless i's don't fold to the plain "i"
+fn fold_char(c: char) -> impl Iterator<Item = char> {
+    iter::once(c).default_case_fold()
+}
+
+// Finds the first part of the line, starting at or after byte `search_from`, that case folds to the (already folded)
+//  query. Lines are folded char by char as they are compared rather than up front, so nothing is allocated per line.
+fn find_case_insensitive(line: &str, query_folded: &str, search_from: usize) -> Option<Range<usize>> {
+    let starts = line.char_indices().map(|(start, _)| start).chain(iter::once(line.len()));
+    starts
+        .filter(|&start| start >= search_from)
+        .find_map(|start| folded_prefix_length(&line[start..], query_folded).map(|length| start..start + length))
+}
+
+// How much of the start of `text` case folds to `query_folded`, if any of it does - worked out char by char, as folding
+//  can change the length of the text. Matches have to end on a char boundary in the original text.
+fn folded_prefix_length(text: &str, query_folded: &str) -> Option<usize> {
+    let mut expected = query_folded.chars();
+    for (index, c) in text.char_indices() {
+        if expected.as_str().is_empty() {
+            return Some(index);
+        }
+        for folded in fold_char(c) {
+            if expected.next() != Some(folded) {
+                return None;
+            }
+        }
+    }
+    if expected.as_str().is_empty() { Some(text.len()) } else { None }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::{search, search_case_insensitive};
+    use super::{find_matches, Match, Matcher};
+    use crate::Config;
+
+    impl Matcher {
+        fn search<'a>(&self, contents: &'a str) -> Vec<&'a str> {
+            contents
+                .lines()
+                .filter(|l| self.is_match(l))
+                .collect()
+        }
+    }
+
+    fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
+        Matcher::new(&Config::new(query.to_string(), vec![])).unwrap().search(contents)
+    }
+
+    fn search_case_insensitive<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
+        let config = Config { case_sensitive: false, ..Config::new(query.to_string(), vec![]) };
+        Matcher::new(&config).unwrap().search(contents)
+    }
+
+    fn regex_config(query: &str, case_sensitive: bool) -> Config {
+        Config { case_sensitive, regex: true, ..Config::new(query.to_string(), vec![]) }
+    }
 
     #[test]
     fn test_search_case_sensitive_one_result() {
@@ -48,4 +315,229 @@ Trust me.";
         );
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_search_regex() {
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.
+Trust me.";
+
+        let matcher = Matcher::new(&regex_config("^[a-z]+, ", true)).unwrap();
+        assert_eq!(vec!["safe, fast, productive."], matcher.search(contents));
+
+        let matcher = Matcher::new(&regex_config("^(rust|pick)", false)).unwrap();
+        assert_eq!(vec!["Rust:", "Pick three."], matcher.search(contents));
+    }
+
+    #[test]
+    fn test_invalid_regex_is_reported() {
+        let error = Matcher::new(&regex_config("fast(", true)).err().unwrap();
+        assert!(error.starts_with("Invalid regular expression \"fast(\": "), "{}", error);
+    }
+
+    #[test]
+    fn test_find_spans_in_every_mode() {
+        let line = "Trust me, rust is robust. RUST!";
+        let spans = |config: Config| Matcher::new(&config).unwrap().find_spans(line);
+
+        assert_eq!(spans(Config::new("rust".to_string(), vec![])), vec![1..5, 10..14]);
+        assert_eq!(
+            spans(Config { case_sensitive: false, ..Config::new("rust".to_string(), vec![]) }),
+            vec![1..5, 10..14, 26..30]
+        );
+        assert_eq!(spans(regex_config("r[a-z]+t", true)), vec![1..5, 10..14, 18..24]);
+        assert_eq!(
+            spans(Config { whole_words: true, case_sensitive: false, ..Config::new("rust".to_string(), vec![]) }),
+            vec![10..14, 26..30]
+        );
+    }
+
+    #[test]
+    fn test_find_matches_gives_positions_of_each_line() {
+        let contents = "Rust and rust:\r\nsafe, fast, productive.\nPick three.\nTrust me, rust.";
+        let config = Config { case_sensitive: false, ..Config::new("rust".to_string(), vec![]) };
+        assert_eq!(find_matches(&config, contents), Ok(vec![
+            Match { line_number: 1, byte_offset: 0, line: "Rust and rust:", spans: vec![0..4, 9..13] },
+            Match { line_number: 4, byte_offset: 52, line: "Trust me, rust.", spans: vec![1..5, 10..14] },
+        ]));
+
+        let config = Config { invert_match: true, ..Config::new("e".to_string(), vec![]) };
+        assert_eq!(
+            find_matches(&config, contents),
+            Ok(vec![Match { line_number: 1, byte_offset: 0, line: "Rust and rust:", spans: vec![] }])
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive_spans_cover_the_original_text() {
+        // "İ" lowercases to two chars, so positions in the lowercased line don't line up with the original
+        let config = Config { case_sensitive: false, ..Config::new("stanbul".to_string(), vec![]) };
+        let line = "İİstanbul, İstanbul";
+        let spans = Matcher::new(&config).unwrap().find_spans(line);
+        assert_eq!(spans, vec![4..11, 15..22]);
+        assert_eq!(&line[spans[0].clone()], "stanbul");
+    }
+
+    #[test]
+    fn test_case_insensitive_search_folds_unicode_case() {
+        let contents = "\
+Dobrý den
+STRASSE
+Straße
+ΣΟΦΟΣ
+İSTANBUL
+ЗДРАВСТВУЙТЕ
+OLÁ
+こんにちは";
+
+        assert_eq!(vec!["Dobrý den"], search_case_insensitive("DOBRÝ", contents));
+        assert_eq!(vec!["STRASSE", "Straße"], search_case_insensitive("straße", contents));
+        assert_eq!(vec!["STRASSE", "Straße"], search_case_insensitive("STRASSE", contents));
+        assert_eq!(vec!["ΣΟΦΟΣ"], search_case_insensitive("σοφος", contents));
+        // Final sigma "ς" folds to the same "σ" as the capital "Σ"
+        assert_eq!(vec!["ΣΟΦΟΣ"], search_case_insensitive("σοφοσ", contents));
+        // "İ" folds to an "i" with a combining dot, not a plain "i"
+        assert_eq!(vec!["İSTANBUL"], search_case_insensitive("i\u{307}stanbul", contents));
+        assert_eq!(Vec::<&str>::new(), search_case_insensitive("istanbul", contents));
+        assert_eq!(vec!["ЗДРАВСТВУЙТЕ"], search_case_insensitive("Здравствуйте", contents));
+        assert_eq!(vec!["OLÁ"], search_case_insensitive("olá", contents));
+        assert_eq!(vec!["こんにちは"], search_case_insensitive("こんにちは", contents));
+        assert_eq!(Vec::<&str>::new(), search_case_insensitive("ola", contents));
+    }
+
+    #[test]
+    fn test_dotless_i_only_matches_itself() {
+        let contents = "ISPARTA\nısparta";
+        assert_eq!(vec!["ısparta"], search_case_insensitive("ısparta", contents));
+        assert_eq!(vec!["ISPARTA"], search_case_insensitive("isparta", contents));
+        assert_eq!(vec!["ISPARTA"], search_case_insensitive("ISPARTA", contents));
+    }
+
+    #[test]
+    fn test_every_demo_greeting_is_found_case_insensitively() {
+        // The greetings from the strings demo in common-collections
+        let greetings = [
+            "السلام عليكم", "Dobrý den", "Hello", "שָׁלוֹם", "नमस्ते", "こんにちは",
+            "안녕하세요", "你好", "Olá", "Здравствуйте", "Hola",
+        ];
+        let contents = greetings.join("\n").to_uppercase();
+        for greeting in greetings.iter() {
+            let found = search_case_insensitive(greeting, &contents);
+            assert_eq!(found.len(), 1, "{}", greeting);
+            assert_eq!(found[0], greeting.to_uppercase());
+        }
+    }
+
+    #[test]
+    fn test_case_insensitive_spans_end_on_char_boundaries() {
+        let config = Config { case_sensitive: false, ..Config::new("ss".to_string(), vec![]) };
+        let matcher = Matcher::new(&config).unwrap();
+        // "ß" folds to "ss", so a match for "ss" covers the whole of it - but only half of "ßß" can't be matched
+        assert_eq!(matcher.find_spans("Straße"), vec![4..6]);
+        assert_eq!(matcher.find_spans("Sss"), vec![0..2]);
+        let empty = Config { case_sensitive: false, ..Config::new(String::new(), vec![]) };
+        assert!(Matcher::new(&empty).unwrap().is_match(""));
+        assert_eq!(Matcher::new(&empty).unwrap().find_spans("ab"), vec![0..0, 1..1, 2..2]);
+    }
+
+    fn patterns_config(patterns: &[&str], match_all: bool) -> Config {
+        let patterns = patterns.iter().map(|pattern| pattern.to_string()).collect();
+        Config { patterns, match_all, ..Config::new(String::new(), vec![]) }
+    }
+
+    #[test]
+    fn test_search_for_any_or_all_of_several_patterns() {
+        let contents = "\
+ERROR E1042 disk full
+INFO disk checked
+ERROR E2001 timeout
+WARN E1042 retrying";
+
+        let any = Matcher::new(&patterns_config(&["E1042", "timeout"], false)).unwrap();
+        assert_eq!(vec!["ERROR E1042 disk full", "ERROR E2001 timeout", "WARN E1042 retrying"], any.search(contents));
+        let all = Matcher::new(&patterns_config(&["ERROR", "disk"], true)).unwrap();
+        assert_eq!(vec!["ERROR E1042 disk full"], all.search(contents));
+
+        let all_case_insensitive = Config { case_sensitive: false, ..patterns_config(&["error", "TIMEOUT"], true) };
+        assert_eq!(vec!["ERROR E2001 timeout"], Matcher::new(&all_case_insensitive).unwrap().search(contents));
+        let any_regex = Config { regex: true, ..patterns_config(&["^WARN", r"E2\d+"], false) };
+        let any_regex = Matcher::new(&any_regex).unwrap();
+        assert_eq!(vec!["ERROR E2001 timeout", "WARN E1042 retrying"], any_regex.search(contents));
+
+        // As in grep, an empty pattern file matches nothing
+        assert!(Matcher::new(&patterns_config(&[], false)).unwrap().search(contents).is_empty());
+        assert!(Matcher::new(&patterns_config(&[], true)).unwrap().search(contents).is_empty());
+    }
+
+    #[test]
+    fn test_spans_of_several_patterns_are_ordered_and_merged() {
+        let matcher = Matcher::new(&patterns_config(&["disk", "E10", "1042", "full"], false)).unwrap();
+        assert_eq!(matcher.find_spans("ERROR E1042 disk full"), vec![6..11, 12..16, 17..21]);
+        let matcher = Matcher::new(&patterns_config(&["", ""], false)).unwrap();
+        assert_eq!(matcher.find_spans("ab"), vec![0..0, 1..1, 2..2]);
+    }
+
+    #[test]
+    fn test_search_whole_words() {
+        let contents = "\
+safe, fast, productive.
+Duct tape.
+duct.tape";
+
+        let config = Config { whole_words: true, case_sensitive: false, ..Config::new("duct".to_string(), vec![]) };
+        assert_eq!(vec!["Duct tape.", "duct.tape"], Matcher::new(&config).unwrap().search(contents));
+        let config = Config { whole_words: true, ..Config::new("duct".to_string(), vec![]) };
+        assert_eq!(vec!["duct.tape"], Matcher::new(&config).unwrap().search(contents));
+    }
+
+    #[test]
+    fn test_whole_words_are_bounded_by_unicode_word_characters() {
+        let words = |query: &str, case_sensitive: bool| {
+            let config = Config { whole_words: true, case_sensitive, ..Config::new(query.to_string(), vec![]) };
+            Matcher::new(&config).unwrap()
+        };
+        assert!(!words("caf", true).is_match("café au lait"));
+        assert!(words("café", true).is_match("café au lait"));
+        // The virama after "स" is a combining mark, so part of the word
+        assert!(!words("नमस", true).is_match("नमस्ते"));
+        assert!(!words("duct", true).is_match("duct_tape"));
+        assert!(words("straße", false).is_match("STRASSE!"));
+        assert!(!words("straße", false).is_match("STRASSEN"));
+        // A match inside a longer word is passed over for a later one that overlaps it
+        assert_eq!(words("aa", true).find_spans("aaa aa"), vec![4..6]);
+        assert_eq!(words("ΣΟΦΟΣ", false).find_spans("σοφος, σοφοσ σοφοςσ"), vec![0..10, 12..22]);
+
+        let regex = Config { whole_words: true, regex: true, ..Config::new("r[a-z]+t".to_string(), vec![]) };
+        assert_eq!(Matcher::new(&regex).unwrap().find_spans("Trust me, rust is robust."), vec![10..14, 18..24]);
+        // A shorter alternative that is only part of a word doesn't stop a longer one being tried at the same place
+        let alternatives = Config { whole_words: true, regex: true, ..Config::new("foo|foobar".to_string(), vec![]) };
+        assert_eq!(Matcher::new(&alternatives).unwrap().find_spans("foobar, foo, foobarbaz"), vec![0..6, 8..11]);
+        let punctuated = Config { whole_words: true, regex: true, ..Config::new("-v|--verbose".to_string(), vec![]) };
+        assert_eq!(Matcher::new(&punctuated).unwrap().find_spans("ls --verbose"), vec![3..12]);
+    }
+
+    #[test]
+    fn test_search_whole_lines() {
+        let contents = "\
+Duct tape.
+Duct tape. Pick three.
+duct TAPE.";
+
+        let lines = |config: Config| Matcher::new(&Config { whole_lines: true, ..config }).unwrap().search(contents);
+        assert_eq!(vec!["Duct tape."], lines(Config::new("Duct tape.".to_string(), vec![])));
+        assert_eq!(
+            vec!["Duct tape.", "duct TAPE."],
+            lines(Config { case_sensitive: false, ..Config::new("DUCT tape.".to_string(), vec![]) })
+        );
+        // The first alternative matches only part of the line, but the second matches all of it
+        let regex = Config { regex: true, ..Config::new("Duct|Duct tape.".to_string(), vec![]) };
+        assert_eq!(vec!["Duct tape."], lines(regex));
+        // Whole lines win over whole words
+        assert!(lines(Config { whole_words: true, ..Config::new("Duct".to_string(), vec![]) }).is_empty());
+
+        let config = Config { whole_lines: true, ..Config::new("Duct tape.".to_string(), vec![]) };
+        assert_eq!(Matcher::new(&config).unwrap().find_spans("Duct tape."), vec![0..10]);
+    }
+
+}
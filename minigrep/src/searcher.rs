@@ -0,0 +1,475 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+use std::ops::Range;
+
+use crate::engine::LiteralEngine;
+use crate::files::is_binary;
+use crate::search::{Match, Matcher};
+use crate::Config;
+
+// Large enough to make scanning whole blocks worthwhile, small enough to keep memory use bounded
+const BLOCK_SIZE: usize = 64 * 1024;
+
+// Searches the input, writing results as soon as they are found, so memory use is bounded by the longest line (and
+//  any lines of context) rather than the size of the input. Invalid UTF-8 is replaced rather than rejected, as grep
+//  does for log files with the odd corrupted line. `label` is the file path to prefix results with, if there is more
+//  than one file, and `color` whether to highlight the output. Returns whether the input counts towards a successful
+//  exit status - it had a selected line or, when listing files without any, it didn't.
+pub(crate) fn search_reader<R: BufRead, W: Write>(
+    config: &Config, matcher: &Matcher, mut reader: R, label: Option<&str>, color: bool, out: &mut W
+) -> io::Result<bool> {
+    // Listing files, or only giving an exit status, just needs to know whether there is a selected line
+    let max_count = if only_checks_for_a_match(config) { Some(1) } else { config.max_count };
+    let mut printer = Printer { config, label, color, out, last_printed_line: None };
+    let has_context = config.before_context > 0 || config.after_context > 0;
+    let count = match matcher.block_engine() {
+        // Binary files are never searched, but still count as having no selected lines - so -L lists them and -c
+        //  gives them a count of 0
+        _ if is_binary(reader.fill_buf()?) => 0,
+        Some(engine) if !config.invert_match && !has_context => {
+            search_blocks(config, matcher, engine, reader, max_count, &mut printer)?
+        }
+        _ => search_lines(config, matcher, reader, max_count, &mut printer)?,
+    };
+
+    if config.quiet {
+        // Only the exit status is wanted
+    } else if config.files_with_matches || config.files_without_match {
+        if (count > 0) == config.files_with_matches {
+            printer.write_colored(label.unwrap_or_default(), PATH_COLOR)?;
+            writeln!(printer.out)?;
+        }
+    } else if config.count {
+        printer.print_prefix(None, ':')?;
+        writeln!(printer.out, "{}", count)?;
+    }
+    Ok(if config.files_without_match { count == 0 } else { count > 0 })
+}
+
+fn only_checks_for_a_match(config: &Config) -> bool {
+    config.files_with_matches || config.files_without_match || config.quiet
+}
+
+fn prints_lines(config: &Config) -> bool {
+    !config.count && !only_checks_for_a_match(config)
+}
+
+// Works through the input a line at a time, checking each against the matcher - the general case, which handles
+//  inverted matches and context lines. Returns the number of lines selected, stopping at `max_count` if there is one.
+fn search_lines<R: BufRead, W: Write>(
+    config: &Config, matcher: &Matcher, mut reader: R, max_count: Option<usize>, printer: &mut Printer<W>
+) -> io::Result<usize> {
+    // Lines that might be needed as context for a later match
+    let mut before_context: VecDeque<(usize, usize, String)> = VecDeque::with_capacity(config.before_context);
+    let mut after_context_remaining = 0;
+    let mut buffer = Vec::new();
+    let mut line_number = 0;
+    let mut byte_offset = 0;
+    let mut count = 0;
+    loop {
+        // Once the limit is reached, the only lines still wanted are those of context after the last one selected
+        let limit_reached = max_count.is_some_and(|max_count| count >= max_count);
+        if limit_reached && after_context_remaining == 0 {
+            break;
+        }
+        buffer.clear();
+        let bytes_read = reader.read_until(b'\n', &mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        line_number += 1;
+        let line_offset = byte_offset;
+        byte_offset += bytes_read;
+        let line = decode_line(&buffer);
+
+        if limit_reached || matcher.is_match(&line) == config.invert_match {
+            if after_context_remaining > 0 {
+                after_context_remaining -= 1;
+                printer.print_context(line_number, line_offset, &line)?;
+            } else if config.before_context > 0 {
+                if before_context.len() == config.before_context {
+                    before_context.pop_front();
+                }
+                before_context.push_back((line_number, line_offset, line.into_owned()));
+            }
+            continue;
+        }
+        count += 1;
+
+        if prints_lines(config) {
+            for (context_line_number, context_offset, context_line) in before_context.drain(..) {
+                printer.print_context(context_line_number, context_offset, &context_line)?;
+            }
+            let spans = if config.invert_match { vec![] } else { matcher.find_spans(&line) };
+            printer.print_match(&Match { line_number, byte_offset: line_offset, line: &line, spans })?;
+            after_context_remaining = config.after_context;
+        }
+    }
+    Ok(count)
+}
+
+// Scans a block of whole lines at a time with the matcher's engine, only splitting out the lines around each hit and
+//  counting newlines in between, rather than checking every line. Returns the number of lines selected, stopping at
+//  `max_count` if there is one.
+fn search_blocks<R: BufRead, W: Write>(
+    config: &Config, matcher: &Matcher, engine: &LiteralEngine, mut reader: R, max_count: Option<usize>,
+    printer: &mut Printer<W>
+) -> io::Result<usize> {
+    let mut block: Vec<u8> = Vec::with_capacity(BLOCK_SIZE);
+    let mut block_has_newline = false;
+    let mut end_of_input = false;
+    // The position of the start of the block in the input
+    let mut block_offset = 0;
+    let mut lines_before_block = 0;
+    let mut count = 0;
+    let limit_reached = |count| max_count.is_some_and(|max_count| count >= max_count);
+    while !limit_reached(count) {
+        // Top up the block, reading on until it holds at least one whole line so that no line is split between blocks
+        while !end_of_input && (block.len() < BLOCK_SIZE || !block_has_newline) {
+            let available = reader.fill_buf()?;
+            if available.is_empty() {
+                end_of_input = true;
+            } else {
+                let length = available.len();
+                block_has_newline |= available.contains(&b'\n');
+                block.extend_from_slice(available);
+                reader.consume(length);
+            }
+        }
+        if block.is_empty() {
+            break;
+        }
+        let end = if end_of_input {
+            block.len()
+        } else {
+            block.iter().rposition(|&byte| byte == b'\n').map_or(block.len(), |last_newline| last_newline + 1)
+        };
+        let lines = &block[..end];
+
+        let mut line_number = lines_before_block;
+        let mut position = 0;
+        while position < lines.len() && !limit_reached(count) {
+            let hit = match engine.find(&lines[position..]) {
+                Some(hit) => position + hit,
+                None => break,
+            };
+            let line_start = lines[position..hit].iter().rposition(|&byte| byte == b'\n')
+                .map_or(position, |newline| position + newline + 1);
+            let line_end = lines[hit..].iter().position(|&byte| byte == b'\n')
+                .map_or(lines.len(), |newline| hit + newline);
+            line_number += count_newlines(&lines[position..line_start]) + 1;
+            count += 1;
+
+            if prints_lines(config) {
+                let line = decode_line(&lines[line_start..line_end]);
+                let spans = matcher.find_spans(&line);
+                let byte_offset = block_offset + line_start;
+                printer.print_match(&Match { line_number, byte_offset, line: &line, spans })?;
+            }
+            position = line_end + 1;
+        }
+        if position < lines.len() {
+            line_number += count_newlines(&lines[position..]);
+        }
+
+        lines_before_block = line_number;
+        block_offset += end;
+        block.drain(..end);
+        block_has_newline = false;
+    }
+    Ok(count)
+}
+
+fn count_newlines(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&byte| byte == b'\n').count()
+}
+
+// Strips the line ending ("\n" or "\r\n", like str::lines) - only borrows unless the line needs invalid bytes replaced
+fn decode_line(buffer: &[u8]) -> Cow<'_, str> {
+    let line = buffer.strip_suffix(b"\n").unwrap_or(buffer);
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    String::from_utf8_lossy(line)
+}
+
+// SGR parameters for each part of the output, matching grep's defaults
+const MATCH_COLOR: &str = "01;31";
+const PATH_COLOR: &str = "35";
+const POSITION_COLOR: &str = "32";
+const SEPARATOR_COLOR: &str = "36";
+
+// Writes lines in grep's format - "path:line:offset:text" for matches, and the same with "-" separators for context,
+//  with a "--" line between groups of lines that aren't next to each other
+struct Printer<'a, W: Write> {
+    config: &'a Config,
+    label: Option<&'a str>,
+    color: bool,
+    out: &'a mut W,
+    last_printed_line: Option<usize>,
+}
+
+impl<'a, W: Write> Printer<'a, W> {
+    fn print_match(&mut self, found: &Match) -> io::Result<()> {
+        self.print_line(found.line_number, found.byte_offset, found.line, &found.spans, ':')
+    }
+
+    fn print_context(&mut self, line_number: usize, byte_offset: usize, line: &str) -> io::Result<()> {
+        self.print_line(line_number, byte_offset, line, &[], '-')
+    }
+
+    fn print_line(
+        &mut self, line_number: usize, byte_offset: usize, line: &str, spans: &[Range<usize>], separator: char
+    ) -> io::Result<()> {
+        let has_context = self.config.before_context > 0 || self.config.after_context > 0;
+        if let Some(last_printed_line) = self.last_printed_line {
+            if has_context && last_printed_line + 1 != line_number {
+                self.write_colored("--", SEPARATOR_COLOR)?;
+                writeln!(self.out)?;
+            }
+        }
+        self.last_printed_line = Some(line_number);
+
+        self.print_prefix(Some((line_number, byte_offset)), separator)?;
+        let mut written_up_to = 0;
+        for span in spans {
+            write!(self.out, "{}", &line[written_up_to..span.start])?;
+            self.write_colored(&line[span.clone()], MATCH_COLOR)?;
+            written_up_to = span.end;
+        }
+        writeln!(self.out, "{}", &line[written_up_to..])
+    }
+
+    fn print_prefix(&mut self, position: Option<(usize, usize)>, separator: char) -> io::Result<()> {
+        let separator = separator.to_string();
+        if let Some(label) = self.label {
+            self.write_colored(label, PATH_COLOR)?;
+            self.write_colored(&separator, SEPARATOR_COLOR)?;
+        }
+        if let Some((line_number, byte_offset)) = position {
+            if self.config.line_numbers {
+                self.write_colored(&line_number.to_string(), POSITION_COLOR)?;
+                self.write_colored(&separator, SEPARATOR_COLOR)?;
+            }
+            if self.config.byte_offset {
+                self.write_colored(&byte_offset.to_string(), POSITION_COLOR)?;
+                self.write_colored(&separator, SEPARATOR_COLOR)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_colored(&mut self, text: &str, color: &str) -> io::Result<()> {
+        if self.color && !text.is_empty() {
+            write!(self.out, "\x1b[{}m{}\x1b[m", color, text)
+        } else {
+            write!(self.out, "{}", text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::search_reader;
+    use crate::search::Matcher;
+    use crate::Config;
+    use std::io::{BufReader, Cursor};
+
+    const POEM: &str = "\
+I'm nobody! Who are you?
+Are you nobody, too?
+Then there's a pair of us - don't tell!
+They'd banish us, you know.";
+
+    const LONGER_POEM: &str = "\
+I'm nobody! Who are you?
+Are you nobody, too?
+Then there's a pair of us - don't tell!
+They'd banish us, you know.
+
+How dreary to be somebody!
+How public, like a frog
+To tell your name the livelong day
+To an admiring bog!";
+
+    fn run_search(config: &Config, contents: &[u8], label: Option<&str>) -> String {
+        let matcher = Matcher::new(config).unwrap();
+        let mut out = Vec::new();
+        search_reader(config, &matcher, Cursor::new(contents), label, false, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn config(query: &str) -> Config {
+        Config::new(query.to_string(), vec![])
+    }
+
+    #[test]
+    fn test_matching_lines_are_written_with_optional_labels_and_line_numbers() {
+        assert_eq!(
+            run_search(&config("nobody"), POEM.as_bytes(), None),
+            "I'm nobody! Who are you?\nAre you nobody, too?\n"
+        );
+        assert_eq!(
+            run_search(&Config { line_numbers: true, ..config("us") }, POEM.as_bytes(), Some("poem.txt")),
+            "poem.txt:3:Then there's a pair of us - don't tell!\npoem.txt:4:They'd banish us, you know.\n"
+        );
+    }
+
+    #[test]
+    fn test_byte_offsets_are_of_the_start_of_each_line() {
+        let config = Config { byte_offset: true, line_numbers: true, ..config("nobody") };
+        assert_eq!(
+            run_search(&config, "caf\u{e9}\r\nnobody\nnobody".as_bytes(), None),
+            "2:7:nobody\n3:14:nobody\n"
+        );
+    }
+
+    #[test]
+    fn test_context_lines_are_separated_between_groups() {
+        let config = Config { line_numbers: true, before_context: 1, after_context: 1, ..config("tell") };
+        assert_eq!(
+            run_search(&config, LONGER_POEM.as_bytes(), None),
+            "\
+2-Are you nobody, too?
+3:Then there's a pair of us - don't tell!
+4-They'd banish us, you know.
+--
+7-How public, like a frog
+8:To tell your name the livelong day
+9-To an admiring bog!
+"
+        );
+    }
+
+    #[test]
+    fn test_overlapping_and_adjacent_context_is_printed_once_without_separators() {
+        let config = Config { before_context: 2, after_context: 1, ..config("body") };
+        assert_eq!(
+            run_search(&config, LONGER_POEM.as_bytes(), Some("poem.txt")),
+            "\
+poem.txt:I'm nobody! Who are you?
+poem.txt:Are you nobody, too?
+poem.txt-Then there's a pair of us - don't tell!
+poem.txt-They'd banish us, you know.
+poem.txt-
+poem.txt:How dreary to be somebody!
+poem.txt-How public, like a frog
+"
+        );
+    }
+
+    #[test]
+    fn test_matches_are_highlighted_when_colored() {
+        let config = Config { case_sensitive: false, line_numbers: true, ..config("YOU") };
+        let matcher = Matcher::new(&config).unwrap();
+        let mut out = Vec::new();
+        let contents = Cursor::new("Are you nobody, you?");
+        search_reader(&config, &matcher, contents, Some("poem.txt"), true, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[35mpoem.txt\x1b[m\x1b[36m:\x1b[m\x1b[32m1\x1b[m\x1b[36m:\x1b[m\
+             Are \x1b[01;31myou\x1b[m nobody, \x1b[01;31myou\x1b[m?\n"
+        );
+    }
+
+    #[test]
+    fn test_inverted_counts_and_files_with_matches() {
+        assert_eq!(
+            run_search(&Config { invert_match: true, ..config("you") }, POEM.as_bytes(), None),
+            "Then there's a pair of us - don't tell!\n"
+        );
+        let count = Config { count: true, ..config("you") };
+        assert_eq!(run_search(&count, POEM.as_bytes(), Some("poem.txt")), "poem.txt:3\n");
+        let files_with_matches = Config { files_with_matches: true, ..config("you") };
+        assert_eq!(run_search(&files_with_matches, POEM.as_bytes(), Some("poem.txt")), "poem.txt\n");
+        assert_eq!(run_search(&files_with_matches, b"no match here", Some("poem.txt")), "");
+    }
+
+    #[test]
+    fn test_max_count_stops_after_enough_lines_but_still_prints_their_context() {
+        let limited = Config { max_count: Some(2), line_numbers: true, ..config("you") };
+        assert_eq!(run_search(&limited, POEM.as_bytes(), None), "1:I'm nobody! Who are you?\n2:Are you nobody, too?\n");
+        let with_context = Config { after_context: 2, ..limited };
+        assert_eq!(
+            run_search(&with_context, POEM.as_bytes(), None),
+            "1:I'm nobody! Who are you?\n2:Are you nobody, too?\n3-Then there's a pair of us - don't tell!\n\
+             4-They'd banish us, you know.\n"
+        );
+        let counted = Config { max_count: Some(1), count: true, invert_match: true, ..config("nobody") };
+        assert_eq!(run_search(&counted, LONGER_POEM.as_bytes(), None), "1\n");
+        assert_eq!(run_search(&Config { max_count: Some(0), ..config("you") }, POEM.as_bytes(), None), "");
+    }
+
+    #[test]
+    fn test_files_without_match_and_quiet_report_the_right_success() {
+        let search = |config: &Config, contents: &str| {
+            let matcher = Matcher::new(config).unwrap();
+            let mut out = Vec::new();
+            let success = search_reader(config, &matcher, Cursor::new(contents), Some("poem.txt"), false, &mut out);
+            (String::from_utf8(out).unwrap(), success.unwrap())
+        };
+        let without_match = Config { files_without_match: true, ..config("frog") };
+        assert_eq!(search(&without_match, POEM), ("poem.txt\n".to_string(), true));
+        assert_eq!(search(&without_match, LONGER_POEM), (String::new(), false));
+        let quiet = Config { quiet: true, count: true, ..config("frog") };
+        assert_eq!(search(&quiet, LONGER_POEM), (String::new(), true));
+        assert_eq!(search(&quiet, POEM), (String::new(), false));
+        assert_eq!(search(&config("frog"), POEM), (String::new(), false));
+    }
+
+    #[test]
+    fn test_several_patterns_are_found_whether_scanning_blocks_or_lines() {
+        let patterns = vec!["frog".to_string(), "bog".to_string(), "nobody".to_string()];
+        let any = Config { patterns, line_numbers: true, ..config("") };
+        assert_eq!(
+            run_search(&any, LONGER_POEM.as_bytes(), None),
+            "1:I'm nobody! Who are you?\n2:Are you nobody, too?\n7:How public, like a frog\n9:To an admiring bog!\n"
+        );
+        // Context lines mean going line by line rather than scanning blocks
+        let with_context = Config { after_context: 1, ..any };
+        assert_eq!(
+            run_search(&with_context, LONGER_POEM.as_bytes(), None),
+            "\
+1:I'm nobody! Who are you?
+2:Are you nobody, too?
+3-Then there's a pair of us - don't tell!
+--
+7:How public, like a frog
+8-To tell your name the livelong day
+9:To an admiring bog!
+"
+        );
+
+        let all = Config { patterns: vec!["you".to_string(), "too".to_string()], match_all: true, ..config("") };
+        assert_eq!(run_search(&all, LONGER_POEM.as_bytes(), None), "Are you nobody, too?\n");
+    }
+
+    #[test]
+    fn test_invalid_utf8_and_crlf_line_endings_are_handled() {
+        let contents = b"caf\xe9 au lait\r\nnothing\r\nlait\xff\xfe";
+        assert_eq!(run_search(&config("lait"), contents, None), "caf\u{FFFD} au lait\nlait\u{FFFD}\u{FFFD}\n");
+    }
+
+    #[test]
+    fn test_binary_input_is_skipped() {
+        let binary = b"\x7fELF\x02\x01\x01\x00\x00";
+        assert_eq!(run_search(&config("ELF"), binary, None), "");
+        assert_eq!(run_search(&Config { invert_match: true, ..config("ELF") }, binary, None), "");
+        assert_eq!(run_search(&Config { count: true, ..config("ELF") }, binary, Some("a.out")), "a.out:0\n");
+        let files_with_matches = Config { files_with_matches: true, ..config("ELF") };
+        assert_eq!(run_search(&files_with_matches, binary, Some("a.out")), "");
+        let files_without_match = Config { files_without_match: true, ..config("ELF") };
+        assert_eq!(run_search(&files_without_match, binary, Some("a.out")), "a.out\n");
+    }
+
+    #[test]
+    fn test_lines_longer_than_the_read_buffer_are_kept_whole() {
+        let long_line = format!("{}needle{}", "a".repeat(100), "b".repeat(100));
+        let contents = format!("first\n{}\nlast", long_line);
+        let config = config("needle");
+        let matcher = Matcher::new(&config).unwrap();
+        let mut out = Vec::new();
+        let reader = BufReader::with_capacity(16, Cursor::new(contents.as_bytes()));
+        search_reader(&config, &matcher, reader, None, false, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), format!("{}\n", long_line));
+    }
+}